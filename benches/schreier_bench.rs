@@ -2,8 +2,9 @@
 extern crate criterion;
 
 use criterion::Criterion;
+use rust_schreier::groups::{mathieu12, rubik};
 use rust_schreier::perm::Perm;
-use rust_schreier::schreier::order;
+use rust_schreier::schreier::{incrementally_build_bsgs, order, random_element, sift};
 
 fn star_benchmark_10(c: &mut Criterion) {
     // Star, G = <(0 n-1), (1 n-1), ...> = S_n, |G| = n!
@@ -50,10 +51,47 @@ fn star_benchmark_30(c: &mut Criterion) {
         })
     });
 }
+fn rubik_point_type_benchmark(c: &mut Criterion) {
+    // The same BSGS computation with 8-byte and 1-byte points
+    let (n, gen) = rubik::generators();
+    c.bench_function("rubik bsgs usize", |b| {
+        b.iter(|| incrementally_build_bsgs(n, &[], &gen, rand::thread_rng()))
+    });
+    let gen: Vec<Perm<u8>> = gen.iter().map(Perm::cast).collect();
+    c.bench_function("rubik bsgs u8", |b| {
+        b.iter(|| incrementally_build_bsgs(n, &[], &gen, rand::thread_rng()))
+    });
+}
+fn sift_benchmark(c: &mut Criterion) {
+    // Sifting through stored inverse representatives, for membership tests
+    // and for the Schreier generators checked while building a BSGS
+    for (name, (n, gen)) in [
+        ("rubik", rubik::generators()),
+        ("m12", mathieu12::generators()),
+    ] {
+        c.bench_function(&format!("{} bsgs", name), |b| {
+            b.iter(|| incrementally_build_bsgs(n, &[], &gen, rand::thread_rng()))
+        });
+        let mut rnd = rand::thread_rng();
+        let (beta_transversals, _) = incrementally_build_bsgs(n, &[], &gen, &mut rnd);
+        let elements: Vec<Perm> = (0..1000)
+            .map(|_| random_element(n, &beta_transversals, &mut rnd))
+            .collect();
+        c.bench_function(&format!("{} sift 1000", name), |b| {
+            b.iter(|| {
+                elements
+                    .iter()
+                    .all(|g| sift(g, &beta_transversals).is_identity())
+            })
+        });
+    }
+}
 criterion_group!(
     benches,
     star_benchmark_10,
     star_benchmark_20,
-    star_benchmark_30
+    star_benchmark_30,
+    rubik_point_type_benchmark,
+    sift_benchmark
 );
 criterion_main!(benches);
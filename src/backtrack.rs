@@ -0,0 +1,230 @@
+use crate::perm::Perm;
+use crate::transversal::{orbit, Transversal};
+
+// Reference: Á. Seress, Permutation Group Algorithms, Chapter 9.
+
+/// Finds an element g of G = <beta_transversals> such that property(g) holds.
+///
+/// Elements of G are visited as sequences of base images. A partial sequence
+/// [(beta_0, gamma_0), ..., (beta_j, gamma_j)] is extended only if
+/// feasible(partial) returns true, so feasible must never reject a partial
+/// sequence that extends to an element satisfying property.
+pub fn element_search(
+    n: usize,
+    beta_transversals: &[(usize, Transversal)],
+    property: impl Fn(&Perm) -> bool,
+    feasible: impl Fn(&[(usize, usize)]) -> bool,
+) -> Option<Perm> {
+    let mut images = vec![];
+    let search = Search {
+        n,
+        beta_transversals,
+        found: &[],
+        orbit_lens: &vec![1; beta_transversals.len()],
+        property: &property,
+        feasible: &feasible,
+    };
+    search.dfs(0, &Perm::e(n), &mut images)
+}
+
+/// Returns a generator set of the subgroup {g in G | property(g)}.
+///
+/// property must define a subgroup of G, and feasible must satisfy the same
+/// condition as in element_search. known is a (possibly empty) list of elements
+/// known to be in the subgroup; they are used for pruning and are returned
+/// as a part of the generator set.
+///
+/// Levels are processed from the bottom of the stabilizer chain. When level i is
+/// processed, K = {g in G^{(i+1)} | property(g)} is already generated by the
+/// elements found so far, and it suffices to find one element for each orbit
+/// of {g in G^{(i)} | property(g)} on beta_i^{G^{(i)}}.
+pub fn subgroup_search(
+    n: usize,
+    beta_transversals: &[(usize, Transversal)],
+    known: &[Perm],
+    property: impl Fn(&Perm) -> bool,
+    feasible: impl Fn(&[(usize, usize)]) -> bool,
+) -> Vec<Perm> {
+    let mut found: Vec<Perm> = vec![];
+    for i in (0..beta_transversals.len()).rev() {
+        let (beta, ref transversal) = beta_transversals[i];
+        // Known elements that lie in G^{(i)}
+        let mut cur_gen: Vec<Perm> = known
+            .iter()
+            .filter(|perm| beta_transversals[..i].iter().all(|&(b, _)| perm[b] == b))
+            .cloned()
+            .collect();
+        cur_gen.extend_from_slice(&found);
+        let mut reached = vec![false; n];
+        for w in orbit(n, &cur_gen, beta) {
+            reached[w] = true;
+        }
+        let mut failed = vec![false; n];
+        let mut images: Vec<(usize, usize)> = beta_transversals[..i]
+            .iter()
+            .map(|&(b, _)| (b, b))
+            .collect();
+        for gamma in 0..n {
+            let repr = match transversal[gamma] {
+                None => continue,
+                Some(ref repr) => repr,
+            };
+            if reached[gamma] || failed[gamma] {
+                continue;
+            }
+            images.push((beta, gamma));
+            let result = if feasible(&images) {
+                // |beta_l^{K^{(l)}}| for each level l
+                let orbit_lens: Vec<usize> = (0..beta_transversals.len())
+                    .map(|l| {
+                        if l <= i {
+                            return 1;
+                        }
+                        let stabilizer: Vec<Perm> = cur_gen
+                            .iter()
+                            .filter(|k| beta_transversals[..l].iter().all(|&(b, _)| k[b] == b))
+                            .cloned()
+                            .collect();
+                        orbit(n, &stabilizer, beta_transversals[l].0).len()
+                    })
+                    .collect();
+                let search = Search {
+                    n,
+                    beta_transversals,
+                    found: &cur_gen,
+                    orbit_lens: &orbit_lens,
+                    property: &property,
+                    feasible: &feasible,
+                };
+                search.dfs(i + 1, repr, &mut images)
+            } else {
+                None
+            };
+            images.pop();
+            match result {
+                Some(g) => {
+                    cur_gen.push(g.clone());
+                    found.push(g);
+                    for w in orbit(n, &cur_gen, beta) {
+                        reached[w] = true;
+                    }
+                }
+                None => {
+                    // No point in the orbit of gamma can be reached either.
+                    for w in orbit(n, &cur_gen, gamma) {
+                        failed[w] = true;
+                    }
+                }
+            }
+        }
+    }
+    let mut result = known.to_vec();
+    result.extend(found);
+    result
+}
+
+struct Search<'a, P, F> {
+    n: usize,
+    beta_transversals: &'a [(usize, Transversal)],
+    /// Elements of the subgroup being searched
+    found: &'a [Perm],
+    /// orbit_lens\[l\] = |beta_l^{K^{(l)}}|, where K^{(l)} is the subgroup of <found>
+    /// fixing beta_0, ..., beta_{l-1}
+    orbit_lens: &'a [usize],
+    property: &'a P,
+    feasible: &'a F,
+}
+
+impl<P, F> Search<'_, P, F>
+where
+    P: Fn(&Perm) -> bool,
+    F: Fn(&[(usize, usize)]) -> bool,
+{
+    /// Searches G^{(level)} * y where y is the product of the representatives chosen so far.
+    ///
+    /// Let g be an element of the subgroup with the smallest image of beta_level.
+    /// * If k in <found> fixes all images chosen so far, g * k is also such an element,
+    ///   so the image of beta_level is the smallest in its orbit under such elements.
+    /// * If k is in K^{(level)}, k * g is also such an element, so the image of beta_level
+    ///   is the smallest in g(beta_level^{K^{(level)}}), which is a subset of the candidates.
+    ///
+    /// Images violating these conditions are not tried.
+    fn dfs(&self, level: usize, y: &Perm, images: &mut Vec<(usize, usize)>) -> Option<Perm> {
+        if level == self.beta_transversals.len() {
+            return if (self.property)(y) {
+                Some(y.clone())
+            } else {
+                None
+            };
+        }
+        let (beta, ref transversal) = self.beta_transversals[level];
+        let stabilizer: Vec<Perm> = self
+            .found
+            .iter()
+            .filter(|k| images.iter().all(|&(_, c)| k[c] == c))
+            .cloned()
+            .collect();
+        let smallest = orbit_minima(self.n, &stabilizer);
+        let mut candidates: Vec<(usize, &Perm)> = transversal
+            .iter()
+            .flatten()
+            // repr maps beta to repr[beta], and y maps it further.
+            .map(|repr| (y[repr[beta]], repr))
+            .collect();
+        candidates.sort_unstable_by_key(|&(image, _)| image);
+        // The largest orbit_lens[level] - 1 candidates are never the smallest.
+        candidates.truncate(candidates.len() + 1 - self.orbit_lens[level]);
+        for (image, repr) in candidates {
+            if smallest[image] != image {
+                continue;
+            }
+            images.push((beta, image));
+            let result = if (self.feasible)(images) {
+                self.dfs(level + 1, &repr.compose(y), images)
+            } else {
+                None
+            };
+            images.pop();
+            if result.is_some() {
+                return result;
+            }
+        }
+        None
+    }
+}
+
+/// Returns the smallest point in the orbit of each point under <gen>.
+pub(crate) fn orbit_minima(n: usize, gen: &[Perm]) -> Vec<usize> {
+    let mut smallest: Vec<usize> = (0..n).collect();
+    for v in 0..n {
+        if smallest[v] == v {
+            for w in orbit(n, gen, v) {
+                smallest[w] = v;
+            }
+        }
+    }
+    smallest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schreier::{incrementally_build_bsgs, order};
+
+    #[test]
+    fn subgroup_search_test_stabilizer() {
+        // G = S_4, the subgroup of elements stabilizing {0, 1} setwise
+        let n = 4;
+        let gen = vec![Perm::new(vec![1, 2, 3, 0]), Perm::new(vec![1, 0, 2, 3])];
+        let mut rnd = rand::thread_rng();
+        let (beta_transversals, _) = incrementally_build_bsgs(n, &[], &gen, &mut rnd);
+        let stab = subgroup_search(
+            n,
+            &beta_transversals,
+            &[],
+            |g| g[0] <= 1 && g[1] <= 1,
+            |images| images.iter().all(|&(b, c)| (b <= 1) == (c <= 1)),
+        );
+        assert_eq!(order(n, &stab), 4.into());
+    }
+}
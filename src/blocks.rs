@@ -0,0 +1,61 @@
+use crate::perm::Perm;
+
+// Reference: M. D. Atkinson, An algorithm for finding the blocks of a permutation group,
+// Mathematics of Computation 29 (1975), 911-913.
+
+/// Returns the finest G-invariant partition in which a and b are in the same part.
+///
+/// The result is a vector of labels: i and j are in the same part if and only if
+/// result\[i\] == result\[j\]. The label of a part is its smallest element.
+/// If G = \<gen\> is transitive, the parts form the minimal block system
+/// in which a and b lie in the same block.
+pub fn minimal_block(n: usize, gen: &[Perm], a: usize, b: usize) -> Vec<usize> {
+    let mut parent: Vec<usize> = (0..n).collect();
+    fn root(parent: &mut [usize], mut x: usize) -> usize {
+        while parent[x] != x {
+            parent[x] = parent[parent[x]];
+            x = parent[x];
+        }
+        x
+    }
+    let mut que = vec![(a, b)];
+    while let Some((x, y)) = que.pop() {
+        let (x, y) = (root(&mut parent, x), root(&mut parent, y));
+        if x == y {
+            continue;
+        }
+        // The smaller root survives.
+        let (x, y) = if x < y { (x, y) } else { (y, x) };
+        parent[y] = x;
+        // x and y being equivalent implies x^s and y^s being equivalent.
+        for s in gen {
+            que.push((s[x], s[y]));
+        }
+    }
+    (0..n).map(|x| root(&mut parent, x)).collect()
+}
+
+/// Returns the size of each part of a partition given as labels.
+pub fn part_sizes(labels: &[usize]) -> Vec<usize> {
+    let mut size = vec![0; labels.len()];
+    for &l in labels {
+        size[l] += 1;
+    }
+    labels.iter().map(|&l| size[l]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minimal_block_test() {
+        // D_8 = <(0 1 2 3), (0 2)> on the vertices of a square
+        let gen = vec![Perm::new(vec![1, 2, 3, 0]), Perm::new(vec![2, 1, 0, 3])];
+        // Diagonals form blocks.
+        assert_eq!(minimal_block(4, &gen, 0, 2), vec![0, 1, 0, 1]);
+        // Adjacent vertices are not in any nontrivial block.
+        assert_eq!(minimal_block(4, &gen, 0, 1), vec![0, 0, 0, 0]);
+        assert_eq!(part_sizes(&minimal_block(4, &gen, 1, 3)), vec![2; 4]);
+    }
+}
@@ -0,0 +1,300 @@
+use crate::perm::Perm;
+use crate::schreier::{
+    bsgs_order, build_bsgs, reduce_strong_generators, schreier_sims, sift, BsgsOptions,
+};
+use crate::transversal::{transversal_stabilizer, Transversal};
+use num_bigint::BigInt;
+
+/// The first bytes of the binary format
+const MAGIC: &[u8; 4] = b"BSGS";
+/// The largest degree accepted without strong generators, which would bound it by their size
+const MAX_TRIVIAL_DEGREE: usize = 1 << 16;
+
+/// A base and strong generating set of a permutation group of degree n,
+/// together with the transversals of its stabilizer chain.
+///
+/// Only the base and the strong generators are stored by to_bytes and by serde.
+/// Loading a BSGS recomputes the transversals and checks by the Schreier-Sims criterion
+/// that the strong generators are indeed strong, so a loaded BSGS can be trusted.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RawBsgs", into = "RawBsgs")
+)]
+pub struct Bsgs {
+    n: usize,
+    beta_transversals: Vec<(usize, Transversal)>,
+    strong_generators: Vec<Perm>,
+}
+
+/// The serialized form of Bsgs
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct RawBsgs {
+    degree: usize,
+    base: Vec<usize>,
+    strong_generators: Vec<Perm>,
+}
+
+impl Bsgs {
+    /// Builds a BSGS of G = \<gen\> by the Schreier-Sims algorithm.
+    pub fn new(n: usize, gen: &[Perm]) -> Self {
+        Self::with_options(n, gen, &BsgsOptions::default())
+    }
+
+    /// Builds a BSGS of G = \<gen\> as in schreier::build_bsgs.
+    /// Redundant strong generators are removed by reduce_strong_generators.
+    pub fn with_options(n: usize, gen: &[Perm], options: &BsgsOptions) -> Self {
+        let mut rnd = rand::thread_rng();
+        let (beta_transversals, s) = build_bsgs(n, gen, options, &mut rnd);
+        let strong_generators = reduce_strong_generators(n, &beta_transversals, &s);
+        Bsgs {
+            n,
+            beta_transversals,
+            strong_generators,
+        }
+    }
+
+    /// Returns the BSGS with the given base and strong generators,
+    /// or an error if they do not form a BSGS of a group of degree n.
+    pub fn from_parts(
+        n: usize,
+        base: &[usize],
+        strong_generators: &[Perm],
+    ) -> Result<Self, String> {
+        // The degree is checked against the data before anything of size n is allocated.
+        if let Some(g) = strong_generators.iter().find(|g| g.size() != n) {
+            return Err(format!("{:?} is not of degree {}", g, n));
+        }
+        if strong_generators.is_empty() && n > MAX_TRIVIAL_DEGREE {
+            return Err(format!("the degree {} of a trivial group is too large", n));
+        }
+        let mut seen = vec![false; n];
+        for &beta in base {
+            if beta >= n || seen[beta] {
+                return Err(format!("invalid base {:?}", base));
+            }
+            seen[beta] = true;
+        }
+        let mut beta_transversals = vec![];
+        for (i, &beta) in base.iter().enumerate() {
+            let gen: Vec<Perm> = strong_generators
+                .iter()
+                .filter(|g| base[..i].iter().all(|&b| g[b] == b))
+                .cloned()
+                .collect();
+            let (transversal, _) = transversal_stabilizer(n, &gen, beta);
+            beta_transversals.push((beta, transversal));
+        }
+        if schreier_sims(n, &beta_transversals, strong_generators).is_err() {
+            return Err("the strong generators do not form a BSGS with the base".to_string());
+        }
+        Ok(Bsgs {
+            n,
+            beta_transversals,
+            strong_generators: strong_generators.to_vec(),
+        })
+    }
+
+    pub fn degree(&self) -> usize {
+        self.n
+    }
+
+    pub fn base(&self) -> Vec<usize> {
+        self.beta_transversals
+            .iter()
+            .map(|&(beta, _)| beta)
+            .collect()
+    }
+
+    pub fn strong_generators(&self) -> &[Perm] {
+        &self.strong_generators
+    }
+
+    pub fn beta_transversals(&self) -> &[(usize, Transversal)] {
+        &self.beta_transversals
+    }
+
+    pub fn order(&self) -> BigInt {
+        bsgs_order(&self.beta_transversals)
+    }
+
+    /// Checks if g is in the group.
+    pub fn contains(&self, g: &Perm) -> bool {
+        g.size() == self.n && sift(g, &self.beta_transversals).is_identity()
+    }
+
+    /// Returns the compact binary form: the magic bytes "BSGS", followed by the degree,
+    /// the length of the base, the base points, the number of strong generators and
+    /// their images. Numbers are little-endian u32, except that points take 1 or 2 bytes
+    /// if the degree is at most 2^8 or 2^16.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let width = point_width(self.n);
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend((self.n as u32).to_le_bytes());
+        let base = self.base();
+        bytes.extend((base.len() as u32).to_le_bytes());
+        for beta in base {
+            bytes.extend(&(beta as u32).to_le_bytes()[..width]);
+        }
+        bytes.extend((self.strong_generators.len() as u32).to_le_bytes());
+        for g in &self.strong_generators {
+            for i in 0..self.n {
+                bytes.extend(&(g[i] as u32).to_le_bytes()[..width]);
+            }
+        }
+        bytes
+    }
+
+    /// Loads a BSGS written by to_bytes, verifying it as in from_parts.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let Some(mut rest) = bytes.strip_prefix(MAGIC) else {
+            return Err("not a BSGS".to_string());
+        };
+        let n = read(&mut rest, 4)?;
+        let width = point_width(n);
+        let base_len = read(&mut rest, 4)?;
+        let base: Vec<usize> = (0..base_len.min(n))
+            .map(|_| read(&mut rest, width))
+            .collect::<Result<_, _>>()?;
+        if base.len() != base_len {
+            return Err(format!("the base is longer than the degree {}", n));
+        }
+        let count = read(&mut rest, 4)?;
+        if count
+            .checked_mul(n)
+            .and_then(|x| x.checked_mul(width))
+            .is_none_or(|len| len > rest.len())
+        {
+            return Err("unexpected end of data".to_string());
+        }
+        let mut strong_generators = vec![];
+        for _ in 0..count {
+            let images: Vec<usize> = (0..n)
+                .map(|_| read(&mut rest, width))
+                .collect::<Result<_, _>>()?;
+            strong_generators.push(Perm::try_from(images)?);
+        }
+        if !rest.is_empty() {
+            return Err("trailing data".to_string());
+        }
+        Self::from_parts(n, &base, &strong_generators)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawBsgs> for Bsgs {
+    type Error = String;
+    fn try_from(raw: RawBsgs) -> Result<Self, Self::Error> {
+        Bsgs::from_parts(raw.degree, &raw.base, &raw.strong_generators)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Bsgs> for RawBsgs {
+    fn from(bsgs: Bsgs) -> Self {
+        RawBsgs {
+            degree: bsgs.n,
+            base: bsgs.base(),
+            strong_generators: bsgs.strong_generators,
+        }
+    }
+}
+
+/// Reads a little-endian number of width bytes from the front of rest.
+fn read(rest: &mut &[u8], width: usize) -> Result<usize, String> {
+    if rest.len() < width {
+        return Err("unexpected end of data".to_string());
+    }
+    let mut buf = [0; 4];
+    buf[..width].copy_from_slice(&rest[..width]);
+    *rest = &rest[width..];
+    Ok(u32::from_le_bytes(buf) as usize)
+}
+
+/// Returns the number of bytes of a point of a permutation of degree n.
+fn point_width(n: usize) -> usize {
+    if n <= 1 << 8 {
+        1
+    } else if n <= 1 << 16 {
+        2
+    } else {
+        4
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groups::{mathieu12, rubik};
+
+    #[test]
+    fn bytes_test() {
+        for (n, gen) in [mathieu12::generators(), rubik::generators()] {
+            let bsgs = Bsgs::new(n, &gen);
+            let bytes = bsgs.to_bytes();
+            let loaded = Bsgs::from_bytes(&bytes).unwrap();
+            assert_eq!(loaded.base(), bsgs.base());
+            assert_eq!(loaded.order(), bsgs.order());
+            assert!(gen.iter().all(|g| loaded.contains(g)));
+            // Truncated data
+            assert!(Bsgs::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        }
+        // Huge degrees in short inputs are rejected without allocating.
+        let mut bytes = MAGIC.to_vec();
+        for x in [u32::MAX, 0, 1] {
+            bytes.extend(x.to_le_bytes());
+        }
+        assert!(Bsgs::from_bytes(&bytes).is_err());
+        bytes.truncate(bytes.len() - 4);
+        bytes.extend(0u32.to_le_bytes());
+        assert!(Bsgs::from_bytes(&bytes).is_err());
+    }
+    #[test]
+    fn from_parts_test() {
+        // S_4 with base [0, 1, 2]
+        let n = 4;
+        let s = vec![
+            Perm::new(vec![1, 2, 3, 0]),
+            Perm::new(vec![0, 2, 3, 1]),
+            Perm::new(vec![0, 1, 3, 2]),
+        ];
+        let bsgs = Bsgs::from_parts(n, &[0, 1, 2], &s).unwrap();
+        assert_eq!(bsgs.order(), 24.into());
+        // Without (2 3), the stabilizer of 0 and 1 is missed.
+        assert!(Bsgs::from_parts(n, &[0, 1, 2], &s[..2]).is_err());
+        // [0, 1] is not a base.
+        assert!(Bsgs::from_parts(n, &[0, 1], &s).is_err());
+        assert!(Bsgs::from_parts(n, &[0, 0, 1], &s).is_err());
+    }
+    #[test]
+    fn with_options_test() {
+        let (n, gen) = rubik::generators();
+        let options = BsgsOptions {
+            known_order: Some(Bsgs::new(n, &gen).order()),
+            randomized: true,
+            ..Default::default()
+        };
+        let bsgs = Bsgs::with_options(n, &gen, &options);
+        assert_eq!(Some(bsgs.order()), options.known_order);
+        assert!(Bsgs::from_parts(n, &bsgs.base(), bsgs.strong_generators()).is_ok());
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_test() {
+        let (n, gen) = mathieu12::generators();
+        let bsgs = Bsgs::new(n, &gen);
+        let json = serde_json::to_string(&bsgs).unwrap();
+        let loaded: Bsgs = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.order(), 95040.into());
+        let json = r#"{"degree": 3, "base": [0], "strong_generators": [[1, 2, 0]]}"#;
+        assert!(serde_json::from_str::<Bsgs>(json).is_ok());
+        // <(0 1 2), (0 1)> has no base [0].
+        let json = r#"{"degree": 3, "base": [0], "strong_generators": [[1, 2, 0], [1, 0, 2]]}"#;
+        assert!(serde_json::from_str::<Bsgs>(json).is_err());
+        assert!(serde_json::from_str::<Perm>("[1, 1, 0]").is_err());
+        let json = r#"{"degree": 4294967295, "base": [], "strong_generators": []}"#;
+        assert!(serde_json::from_str::<Bsgs>(json).is_err());
+    }
+}
@@ -0,0 +1,120 @@
+use crate::backtrack::orbit_minima;
+use crate::perm::Perm;
+use crate::schreier::incrementally_build_bsgs;
+use std::collections::BTreeMap;
+
+/// Returns the lexicographically smallest image (a_0^g, ..., a_{k-1}^g) of a sequence
+/// under G = \<gen\>, together with such g.
+pub fn canonical_sequence_image(n: usize, gen: &[Perm], sequence: &[usize]) -> (Vec<usize>, Perm) {
+    let mut rnd = rand::thread_rng();
+    // With the sequence as the base, a_i^g depends only on the representatives
+    // of levels 0, ..., i, so the image of each a_i can be minimized in turn
+    // as in min_coset_rep.
+    let (beta_transversals, _) = incrementally_build_bsgs(n, sequence, gen, &mut rnd);
+    let mut g = Perm::e(n);
+    for (_, transversal) in &beta_transversals[..sequence.len()] {
+        let repr = transversal
+            .iter()
+            .enumerate()
+            .filter_map(|(gamma, repr)| repr.as_ref().map(|repr| (g[gamma], repr)))
+            .min_by_key(|&(image, _)| image)
+            .unwrap()
+            .1;
+        g = repr.compose(&g);
+    }
+    (sequence.iter().map(|&a| g[a]).collect(), g)
+}
+
+/// Returns the lexicographically smallest image of a set under G = \<gen\> as a sorted vector,
+/// together with g mapping the set to it.
+///
+/// The smallest image is determined one point at a time. After the first i points
+/// c_0 < ... < c_{i-1} are determined, every image with this prefix is T^k for one of
+/// the current states T = set^h containing the prefix, and k in the pointwise stabilizer K
+/// of the prefix. The next point c_i is the smallest K-orbit minimum of the remaining points
+/// of the states, and each state with a point t in the K-orbit of c_i branches into
+/// the state T^x, where x in K maps t to c_i.
+pub fn canonical_set_image(n: usize, gen: &[Perm], set: &[usize]) -> (Vec<usize>, Perm) {
+    let mut rnd = rand::thread_rng();
+    let mut set = set.to_vec();
+    set.sort_unstable();
+    set.dedup();
+    // Maps each state T to some h with T = set^h.
+    let mut states = BTreeMap::new();
+    states.insert(set.clone(), Perm::e(n));
+    let mut prefix = vec![];
+    let mut stabilizer = gen.to_vec();
+    for _ in 0..set.len() {
+        let smallest = orbit_minima(n, &stabilizer);
+        let is_new = |t: &usize| !prefix.contains(t);
+        let c = states
+            .keys()
+            .flat_map(|state| state.iter().filter(|t| is_new(t)).map(|&t| smallest[t]))
+            .min()
+            .unwrap();
+        let (beta_transversals, s) = incrementally_build_bsgs(n, &[c], &stabilizer, &mut rnd);
+        let transversal = &beta_transversals[0].1;
+        let mut next_states = BTreeMap::new();
+        for (state, h) in &states {
+            for &t in state.iter().filter(|t| is_new(t)) {
+                // repr maps c to t.
+                if let Some(x) = transversal.inverse(t) {
+                    let mut next: Vec<usize> = state.iter().map(|&a| x[a]).collect();
+                    next.sort_unstable();
+                    next_states.entry(next).or_insert_with(|| h.compose(x));
+                }
+            }
+        }
+        states = next_states;
+        prefix.push(c);
+        stabilizer = s.into_iter().filter(|x| x[c] == c).collect();
+    }
+    let g = states.into_values().next().unwrap_or_else(|| Perm::e(n));
+    (prefix, g)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::elements;
+    use crate::groups::mathieu12;
+
+    #[test]
+    fn canonical_sequence_image_test() {
+        // G = <(0 1 2 3)>
+        let n = 4;
+        let gen = vec![Perm::new(vec![1, 2, 3, 0])];
+        let (image, g) = canonical_sequence_image(n, &gen, &[2, 0]);
+        assert_eq!(image, vec![0, 2]);
+        assert_eq!((g[2], g[0]), (0, 2));
+        // G = S_4
+        let gen = vec![Perm::new(vec![1, 2, 3, 0]), Perm::new(vec![1, 0, 2, 3])];
+        let (image, _) = canonical_sequence_image(n, &gen, &[3, 1, 3]);
+        assert_eq!(image, vec![0, 1, 0]);
+    }
+    #[test]
+    fn canonical_set_image_test_m12() {
+        let (n, gen) = mathieu12::generators();
+        let mut rnd = rand::thread_rng();
+        let (beta_transversals, _) = incrementally_build_bsgs(n, &[], &gen, &mut rnd);
+        for set in [
+            vec![11, 5, 7],
+            vec![1, 3, 4, 8, 10, 11],
+            vec![2, 6, 7, 9, 10],
+        ] {
+            let (image, g) = canonical_set_image(n, &gen, &set);
+            let mut mapped: Vec<usize> = set.iter().map(|&a| g[a]).collect();
+            mapped.sort_unstable();
+            assert_eq!(mapped, image);
+            let expected = elements(n, &beta_transversals)
+                .map(|x| {
+                    let mut v: Vec<usize> = set.iter().map(|&a| x[a]).collect();
+                    v.sort_unstable();
+                    v
+                })
+                .min()
+                .unwrap();
+            assert_eq!(image, expected);
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use crate::backtrack::subgroup_search;
+use crate::perm::Perm;
+use crate::schreier::{incrementally_build_bsgs, strip};
+use crate::transversal::Transversal;
+
+/// Returns a generator set of C_G(g), where G = \<gen\>.
+pub fn centralizer(n: usize, gen: &[Perm], g: &Perm) -> Vec<Perm> {
+    subgroup_centralizer(n, gen, std::slice::from_ref(g))
+}
+
+/// Returns a generator set of C_G(H), where G = \<gen\> and H = \<h\>.
+///
+/// The search runs over the BSGS of G. Since an element x of C_G(H) satisfies
+/// (a^s)^x = (a^x)^s for every s in h, the image of a base point determines x on
+/// the whole H-orbit of the point, which in particular maps every cycle of s to a
+/// cycle of s of the same length. Partial base images for which this forced map
+/// is inconsistent are pruned.
+pub fn subgroup_centralizer(n: usize, gen: &[Perm], h: &[Perm]) -> Vec<Perm> {
+    let mut rnd = rand::thread_rng();
+    let (beta_transversals, _) = incrementally_build_bsgs(n, &[], gen, &mut rnd);
+    centralizer_in_bsgs(n, &beta_transversals, h)
+}
+
+/// The same as subgroup_centralizer, but G is given by its BSGS.
+pub(crate) fn centralizer_in_bsgs(
+    n: usize,
+    beta_transversals: &[(usize, Transversal)],
+    h: &[Perm],
+) -> Vec<Perm> {
+    // Elements of G ∩ Z(H) are known to be in C_G(H).
+    let known: Vec<Perm> = h
+        .iter()
+        .filter(|x| h.iter().all(|y| x.compose(y) == y.compose(x)))
+        .filter(|x| strip(x, beta_transversals).1 == Perm::e(n))
+        .cloned()
+        .collect();
+    subgroup_search(
+        n,
+        beta_transversals,
+        &known,
+        |x| h.iter().all(|y| x.compose(y) == y.compose(x)),
+        |images| is_consistent(n, h, h, images),
+    )
+}
+
+/// Returns a generator set of Z(G), where G = \<gen\>.
+pub fn center(n: usize, gen: &[Perm]) -> Vec<Perm> {
+    subgroup_centralizer(n, gen, gen)
+}
+
+/// Checks if the partial map x: a |-> c ((a, c) in images) extends to a bijection
+/// satisfying (a^{from\[i\]})^x = (a^x)^{to\[i\]} for every i.
+pub(crate) fn is_consistent(
+    n: usize,
+    from: &[Perm],
+    to: &[Perm],
+    images: &[(usize, usize)],
+) -> bool {
+    const UNDEF: usize = usize::MAX;
+    let mut map = vec![UNDEF; n];
+    let mut inv = vec![UNDEF; n];
+    let mut que = vec![];
+    for &(a, c) in images {
+        if map[a] != UNDEF {
+            if map[a] != c {
+                return false;
+            }
+            continue;
+        }
+        if inv[c] != UNDEF {
+            return false;
+        }
+        map[a] = c;
+        inv[c] = a;
+        que.push(a);
+        while let Some(x) = que.pop() {
+            for (s, t) in from.iter().zip(to) {
+                let (y, z) = (s[x], t[map[x]]);
+                if map[y] == UNDEF {
+                    if inv[z] != UNDEF {
+                        return false;
+                    }
+                    map[y] = z;
+                    inv[z] = y;
+                    que.push(y);
+                } else if map[y] != z {
+                    return false;
+                }
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groups::{mathieu12, rubik, symmetric};
+    use crate::schreier::order;
+
+    #[test]
+    fn centralizer_test_s6() {
+        let n = 6;
+        let gen = symmetric::generators(n).1;
+        // C(0 1 2 3 4 5) = <(0 1 2 3 4 5)>
+        let c = centralizer(n, &gen, &Perm::new(vec![1, 2, 3, 4, 5, 0]));
+        assert_eq!(order(n, &c), 6.into());
+        // |C((0 1)(2 3))| = 6! / 45 = 16
+        let c = centralizer(n, &gen, &Perm::new(vec![1, 0, 3, 2, 4, 5]));
+        assert_eq!(order(n, &c), 16.into());
+    }
+    #[test]
+    fn subgroup_centralizer_test_klein() {
+        // V = {e, (0 1)(2 3), (0 2)(1 3), (0 3)(1 2)} is self-centralizing in S_4.
+        let n = 4;
+        let v = vec![Perm::new(vec![1, 0, 3, 2]), Perm::new(vec![2, 3, 0, 1])];
+        let c = subgroup_centralizer(n, &symmetric::generators(n).1, &v);
+        assert_eq!(order(n, &c), 4.into());
+    }
+    #[test]
+    fn center_test() {
+        let (n, gen) = mathieu12::generators();
+        assert_eq!(order(n, &center(n, &gen)), 1.into());
+        // The center of Rubik's Cube group is {e, superflip}.
+        let (n, gen) = rubik::generators();
+        assert_eq!(order(n, &center(n, &gen)), 2.into());
+    }
+}
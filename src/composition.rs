@@ -0,0 +1,361 @@
+use crate::blocks::minimal_block;
+use crate::conjugacy::conjugacy_classes;
+use crate::coset::coset_action;
+use crate::homomorphism::Homomorphism;
+use crate::perm::Perm;
+use crate::schreier::{bsgs_order, incrementally_build_bsgs, order, strip};
+use crate::subgroup::{derived_subgroup, normal_closure};
+use crate::transversal::orbit;
+use num_bigint::BigInt;
+
+/// A composition factor G_i / G_{i+1} of a composition series.
+#[derive(Clone, Debug)]
+pub struct CompositionFactor {
+    pub order: BigInt,
+    /// The degree of the permutation representation in which the factor was found.
+    /// A cyclic factor of order p is regarded as acting regularly on p points.
+    pub degree: usize,
+}
+
+impl CompositionFactor {
+    /// Returns the name of the factor, such as "C2", "A8" or "M12",
+    /// if it is determined by the order and the degree.
+    pub fn name(&self) -> Option<String> {
+        let order: u64 = (&self.order).try_into().ok()?;
+        if is_prime(order) {
+            return Some(format!("C{}", order));
+        }
+        // A_8 and PSL(3, 4) are the only nonisomorphic simple groups of order d!/2,
+        // and only A_8 acts on 8 points.
+        let mut half_factorial = 3u64;
+        for d in 4.. {
+            half_factorial = match half_factorial.checked_mul(d) {
+                Some(x) if x <= order => x,
+                _ => break,
+            };
+            if half_factorial == order && (d != 8 || self.degree == 8) {
+                return Some(format!("A{}", d));
+            }
+        }
+        // Simple groups with these orders are unique.
+        let known = [
+            (168, "PSL(2,7)"),
+            (504, "PSL(2,8)"),
+            (660, "PSL(2,11)"),
+            (1092, "PSL(2,13)"),
+            (7920, "M11"),
+            (95040, "M12"),
+            (443520, "M22"),
+            (10200960, "M23"),
+            (244823040, "M24"),
+        ];
+        known
+            .iter()
+            .find(|&&(x, _)| x == order)
+            .map(|&(_, name)| name.to_string())
+    }
+}
+
+/// Returns a composition series G = G_0 > G_1 > ... > G_r = 1 of G = \<gen\>
+/// as generator sets, where G_{i+1} is a maximal normal subgroup of G_i.
+///
+/// The series passes through the derived subgroup if G is not perfect.
+/// Otherwise G is reduced to its action on an orbit or on a block system,
+/// and the series of the image is pulled back and followed by that of the kernel.
+/// A primitive group is split by the normal closure of a conjugacy class representative,
+/// and is simple if there is no such proper normal subgroup.
+/// Returns None if the conjugacy classes of a primitive group are not found
+/// (see conjugacy::conjugacy_classes).
+pub fn composition_series(n: usize, gen: &[Perm]) -> Option<Vec<Vec<Perm>>> {
+    let (mut series, _) = series(n, gen)?;
+    series[0] = gen.to_vec();
+    Some(series)
+}
+
+/// Returns the composition factors G_i / G_{i+1} of the series returned by composition_series,
+/// or None if composition_series gives up.
+pub fn composition_factors(n: usize, gen: &[Perm]) -> Option<Vec<CompositionFactor>> {
+    Some(series(n, gen)?.1)
+}
+
+/// Checks if G = \<gen\> is a simple group.
+/// Returns None if the conjugacy classes of a primitive group are not found.
+pub fn is_simple(n: usize, gen: &[Perm]) -> Option<bool> {
+    let group_order = order(n, gen);
+    if group_order == 1.into() {
+        return Some(false);
+    }
+    if order(n, &derived_subgroup(n, gen)) < group_order {
+        // A simple abelian group has prime order.
+        return Some(group_order.try_into().is_ok_and(is_prime));
+    }
+    match reduction(n, gen) {
+        Some(phi) if !phi.kernel().is_empty() => Some(false),
+        Some(phi) => is_simple(phi.image_degree(), phi.image()),
+        None => Some(primitive_normal_subgroup(n, gen, &group_order)?.is_none()),
+    }
+}
+
+type Series = (Vec<Vec<Perm>>, Vec<CompositionFactor>);
+
+fn series(n: usize, gen: &[Perm]) -> Option<Series> {
+    let group_order = order(n, gen);
+    if group_order == 1.into() {
+        return Some((vec![vec![]], vec![]));
+    }
+    let derived = derived_subgroup(n, gen);
+    if order(n, &derived) < group_order {
+        let (mut series, mut factors) = abelian_series(n, gen, &derived);
+        let (rest, rest_factors) = self::series(n, &derived)?;
+        series.extend(rest.into_iter().skip(1));
+        factors.extend(rest_factors);
+        return Some((series, factors));
+    }
+    if let Some(phi) = reduction(n, gen) {
+        return pull_back(n, &phi);
+    }
+    let normal = match primitive_normal_subgroup(n, gen, &group_order)? {
+        None => {
+            let factor = CompositionFactor {
+                order: group_order,
+                degree: n,
+            };
+            return Some((vec![gen.to_vec(), vec![]], vec![factor]));
+        }
+        Some(normal) => normal,
+    };
+    if order(n, &normal) != n.into() {
+        let images = coset_action(n, gen, &normal);
+        let phi = Homomorphism::new(n, images[0].size(), gen, &images).unwrap();
+        return pull_back(n, &phi);
+    }
+    // A regular normal subgroup N has the point stabilizer G_0 as a complement,
+    // so G / N is isomorphic to G_0.
+    let mut rnd = rand::thread_rng();
+    let (_, s) = incrementally_build_bsgs(n, &[0], gen, &mut rnd);
+    let stabilizer: Vec<Perm> = s.into_iter().filter(|x| x[0] == 0).collect();
+    let (top, mut factors) = self::series(n, &stabilizer)?;
+    let mut series: Vec<Vec<Perm>> = top
+        .into_iter()
+        .map(|h| [h, normal.clone()].concat())
+        .collect();
+    let (rest, rest_factors) = self::series(n, &normal)?;
+    series.extend(rest.into_iter().skip(1));
+    factors.extend(rest_factors);
+    Some((series, factors))
+}
+
+/// Returns a chain from G down to the derived subgroup D with factors of prime order.
+/// Every subgroup containing D is normal in G.
+fn abelian_series(n: usize, gen: &[Perm], derived: &[Perm]) -> Series {
+    let mut rnd = rand::thread_rng();
+    let mut cur = derived.to_vec();
+    let (mut cur_transversals, mut s) = incrementally_build_bsgs(n, &[], &cur, &mut rnd);
+    let mut cur_order = bsgs_order(&cur_transversals);
+    // The chain is built from the bottom.
+    let mut chain = vec![];
+    let mut factors = vec![];
+    for g in gen {
+        if strip(g, &cur_transversals).1 == Perm::e(n) {
+            continue;
+        }
+        let mut next = cur.clone();
+        next.push(g.clone());
+        let base: Vec<usize> = cur_transversals.iter().map(|&(beta, _)| beta).collect();
+        s.push(g.clone());
+        (cur_transversals, s) = incrementally_build_bsgs(n, &base, &s, &mut rnd);
+        let next_order = bsgs_order(&cur_transversals);
+        // g has order index modulo <cur>, which may not fit in u64,
+        // but its prime factors divide the order of g and hence are at most n.
+        let mut index = &next_order / &cur_order;
+        let zero = BigInt::from(0);
+        let mut primes = vec![];
+        for p in 2..=n {
+            while &index % p == zero {
+                primes.push(p);
+                index /= p;
+            }
+        }
+        // With index = p_1 ... p_k, the subgroups <cur, g^(p_{j+1} ... p_k)> form a chain
+        // with factors of order p_j. The powers are computed from g downwards.
+        let mut powers = vec![g.clone()];
+        for &p in primes.iter().skip(1).rev() {
+            let h = powers.last().unwrap().pow(p as i64);
+            powers.push(h);
+        }
+        for (&p, h) in primes.iter().zip(powers.into_iter().rev()) {
+            let mut sub = cur.clone();
+            sub.push(h);
+            chain.push(sub);
+            factors.push(CompositionFactor {
+                order: p.into(),
+                degree: p,
+            });
+        }
+        cur = next;
+        cur_order = next_order;
+    }
+    chain.reverse();
+    factors.reverse();
+    chain.push(derived.to_vec());
+    (chain, factors)
+}
+
+/// Returns the action of G = <gen> on a nontrivial orbit or on a nontrivial block system,
+/// or None if G is primitive.
+fn reduction(n: usize, gen: &[Perm]) -> Option<Homomorphism> {
+    let moved = (0..n).find(|&a| gen.iter().any(|g| g[a] != a))?;
+    let o = orbit(n, gen, moved);
+    if o.len() < n {
+        return Some(Homomorphism::action_on_orbit(n, gen, &o));
+    }
+    (1..n)
+        .map(|b| minimal_block(n, gen, 0, b))
+        .find(|labels| labels.iter().any(|&l| l != 0))
+        .map(|labels| Homomorphism::action_on_blocks(n, gen, &labels))
+}
+
+/// Pulls back a composition series of the image of phi, followed by one of its kernel.
+fn pull_back(n: usize, phi: &Homomorphism) -> Option<Series> {
+    let (top, mut factors) = series(phi.image_degree(), phi.image())?;
+    let mut series: Vec<Vec<Perm>> = top
+        .iter()
+        .map(|h| phi.subgroup_preimage(h).unwrap())
+        .collect();
+    let (rest, rest_factors) = self::series(n, &phi.kernel())?;
+    series.extend(rest.into_iter().skip(1));
+    factors.extend(rest_factors);
+    Some((series, factors))
+}
+
+/// Returns Some(N) for a proper nontrivial normal subgroup N of G = <gen>, Some(None) if G is
+/// simple, and None if conjugacy_classes gives up. G is assumed to be primitive and perfect.
+fn primitive_normal_subgroup(
+    n: usize,
+    gen: &[Perm],
+    group_order: &BigInt,
+) -> Option<Option<Vec<Perm>>> {
+    // A_n is simple for n >= 5.
+    let half_factorial: BigInt = (3..=n).product::<BigInt>();
+    if n >= 5 && *group_order == half_factorial {
+        return Some(None);
+    }
+    // Every nontrivial normal subgroup contains a conjugacy class other than {e}.
+    let normal = conjugacy_classes(n, gen)?
+        .into_iter()
+        .filter(|class| class.representative != Perm::e(n))
+        .map(|class| normal_closure(n, gen, &[class.representative]))
+        .find(|closure| order(n, closure) < *group_order);
+    Some(normal)
+}
+
+fn is_prime(x: u64) -> bool {
+    prime_factors(x).len() == 1
+}
+
+/// Returns the prime factors of x with multiplicity in ascending order.
+fn prime_factors(mut x: u64) -> Vec<u64> {
+    let mut factors = vec![];
+    let mut p = 2;
+    while p * p <= x {
+        while x.is_multiple_of(p) {
+            factors.push(p);
+            x /= p;
+        }
+        p += 1;
+    }
+    if x > 1 {
+        factors.push(x);
+    }
+    factors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groups::{mathieu12, rubik, symmetric};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn is_simple_test() {
+        let (n, gen) = mathieu12::generators();
+        assert_eq!(is_simple(n, &gen), Some(true));
+        assert_eq!(is_simple(5, &symmetric::generators(5).1), Some(false));
+        // A_5 = <(0 1 2), (2 3 4)>
+        let a5 = vec![
+            Perm::new(vec![1, 2, 0, 3, 4]),
+            Perm::new(vec![0, 1, 3, 4, 2]),
+        ];
+        assert_eq!(is_simple(5, &a5), Some(true));
+        // C_5 is simple, C_4 is not.
+        assert_eq!(is_simple(5, &[Perm::new(vec![1, 2, 3, 4, 0])]), Some(true));
+        assert_eq!(is_simple(4, &[Perm::new(vec![1, 2, 3, 0])]), Some(false));
+        assert_eq!(is_simple(3, &[]), Some(false));
+    }
+    #[test]
+    fn composition_series_test_s4() {
+        let n = 4;
+        let series = composition_series(n, &symmetric::generators(n).1).unwrap();
+        let orders: Vec<BigInt> = series.iter().map(|h| order(n, h)).collect();
+        let expected: Vec<BigInt> = vec![24, 12, 4, 2, 1]
+            .into_iter()
+            .map(BigInt::from)
+            .collect();
+        assert_eq!(orders, expected);
+        // G_{i+1} is normal in G_i.
+        let mut rnd = rand::thread_rng();
+        for pair in series.windows(2) {
+            let (sub_transversals, _) = incrementally_build_bsgs(n, &[], &pair[1], &mut rnd);
+            for g in &pair[0] {
+                for x in &pair[1] {
+                    let y = g.inv().compose(x).compose(g);
+                    assert_eq!(strip(&y, &sub_transversals).1, Perm::e(n));
+                }
+            }
+        }
+    }
+    #[test]
+    fn composition_factors_test_m12() {
+        let (n, gen) = mathieu12::generators();
+        let factors = composition_factors(n, &gen).unwrap();
+        assert_eq!(factors.len(), 1);
+        assert_eq!(factors[0].name(), Some("M12".to_string()));
+    }
+    #[test]
+    fn composition_factors_test_rubik() {
+        // Rubik's Cube group has composition factors C2 (12 times), C3 (7 times), A8 and A12.
+        let (n, gen) = rubik::generators();
+        let mut count = BTreeMap::new();
+        for factor in composition_factors(n, &gen).unwrap() {
+            *count.entry(factor.name().unwrap()).or_insert(0) += 1;
+        }
+        let expected: BTreeMap<String, usize> = [("C2", 12), ("C3", 7), ("A8", 1), ("A12", 1)]
+            .into_iter()
+            .map(|(name, k)| (name.to_string(), k))
+            .collect();
+        assert_eq!(count, expected);
+    }
+    #[test]
+    fn composition_factors_test_large_cyclic() {
+        // A cycle of each prime length up to 53, whose order 2 * 3 * ... * 53 exceeds 2^64
+        let primes = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53];
+        let n: usize = primes.iter().sum();
+        let mut images = vec![];
+        for &p in &primes {
+            let start = images.len();
+            images.extend((1..p).map(|i| start + i));
+            images.push(start);
+        }
+        let gen = vec![Perm::new(images)];
+        let series = composition_series(n, &gen).unwrap();
+        assert_eq!(series.len(), primes.len() + 1);
+        let mut orders: Vec<BigInt> = composition_factors(n, &gen)
+            .unwrap()
+            .into_iter()
+            .map(|factor| factor.order)
+            .collect();
+        orders.sort();
+        let primes: Vec<BigInt> = primes.iter().map(|&p| p.into()).collect();
+        assert_eq!(orders, primes);
+    }
+}
@@ -0,0 +1,211 @@
+use crate::backtrack::element_search;
+use crate::centralizer::{centralizer_in_bsgs, is_consistent};
+use crate::elements::elements;
+use crate::perm::Perm;
+use crate::schreier::{bsgs_order, incrementally_build_bsgs, random_element, strip};
+use crate::transversal::Transversal;
+use num_bigint::BigInt;
+use std::collections::BTreeMap;
+
+/// The number of random elements sampled by conjugacy_classes before it gives up
+const MAX_SAMPLES: usize = 1 << 16;
+
+#[derive(Clone, Debug)]
+pub struct ConjugacyClass {
+    pub representative: Perm,
+    /// The number of elements in this class
+    pub size: BigInt,
+    /// The order of the centralizer of the representative
+    pub centralizer_order: BigInt,
+}
+
+/// Returns g in G = \<gen\> with g^{-1} x g = y, or None if x and y are not conjugate in G.
+pub fn is_conjugate(n: usize, gen: &[Perm], x: &Perm, y: &Perm) -> Option<Perm> {
+    let mut rnd = rand::thread_rng();
+    let (beta_transversals, _) = incrementally_build_bsgs(n, &[], gen, &mut rnd);
+    conjugating_element(n, &beta_transversals, x, y)
+}
+
+fn conjugating_element(
+    n: usize,
+    beta_transversals: &[(usize, Transversal)],
+    x: &Perm,
+    y: &Perm,
+) -> Option<Perm> {
+    if x.cycle_type() != y.cycle_type() {
+        return None;
+    }
+    // g^{-1} x g = y <=> (a^x)^g = (a^g)^y for every point a.
+    let from = std::slice::from_ref(x);
+    let to = std::slice::from_ref(y);
+    element_search(
+        n,
+        beta_transversals,
+        |g| x.compose(g) == g.compose(y),
+        |images| is_consistent(n, from, to, images),
+    )
+}
+
+/// Returns the conjugacy classes of G = \<gen\>, sorted by the cycle types of the representatives.
+///
+/// The elements of the center Z(G) form classes of their own, which random sampling
+/// would hardly find. The other representatives are found by sampling uniformly random
+/// elements of G outside Z(G). A sample is compared only with the known representatives
+/// of the same cycle type, and it is a new representative if it is conjugate to none of them.
+/// The search stops when the class sizes |G| / |C_G(x)| sum up to |G|.
+/// It gives up and returns None if |Z(G)| or the number of samples exceeds MAX_SAMPLES,
+/// which happens for groups with many classes or with other small classes.
+pub fn conjugacy_classes(n: usize, gen: &[Perm]) -> Option<Vec<ConjugacyClass>> {
+    sampled_classes(n, gen, MAX_SAMPLES)
+}
+
+/// conjugacy_classes with at most max_samples samples
+fn sampled_classes(n: usize, gen: &[Perm], max_samples: usize) -> Option<Vec<ConjugacyClass>> {
+    let mut rnd = rand::thread_rng();
+    let (beta_transversals, _) = incrementally_build_bsgs(n, &[], gen, &mut rnd);
+    let group_order = bsgs_order(&beta_transversals);
+    let mut buckets: BTreeMap<Vec<usize>, Vec<ConjugacyClass>> = BTreeMap::new();
+    let center = centralizer_in_bsgs(n, &beta_transversals, gen);
+    let (center_transversals, _) = incrementally_build_bsgs(n, &[], &center, &mut rnd);
+    let mut total = bsgs_order(&center_transversals);
+    if total > max_samples.into() {
+        return None;
+    }
+    for z in elements(n, &center_transversals) {
+        buckets
+            .entry(z.cycle_type())
+            .or_default()
+            .push(ConjugacyClass {
+                representative: z,
+                size: 1.into(),
+                centralizer_order: group_order.clone(),
+            });
+    }
+    for _ in 0..max_samples {
+        if total == group_order {
+            return Some(buckets.into_values().flatten().collect());
+        }
+        let candidate = random_element(n, &beta_transversals, &mut rnd);
+        if strip(&candidate, &center_transversals).1 == Perm::e(n) {
+            continue;
+        }
+        let bucket = buckets.entry(candidate.cycle_type()).or_default();
+        if bucket.iter().all(|class| {
+            conjugating_element(n, &beta_transversals, &class.representative, &candidate).is_none()
+        }) {
+            let centralizer =
+                centralizer_in_bsgs(n, &beta_transversals, std::slice::from_ref(&candidate));
+            let (centralizer_transversals, _) =
+                incrementally_build_bsgs(n, &[], &centralizer, &mut rnd);
+            let centralizer_order = bsgs_order(&centralizer_transversals);
+            let size = &group_order / &centralizer_order;
+            total += &size;
+            bucket.push(ConjugacyClass {
+                representative: candidate,
+                size,
+                centralizer_order,
+            });
+        }
+    }
+    // The class equation
+    if total == group_order {
+        return Some(buckets.into_values().flatten().collect());
+    }
+    None
+}
+
+/// Returns the number of elements of G = \<gen\> of each order,
+/// or None if conjugacy_classes gives up.
+pub fn element_orders(n: usize, gen: &[Perm]) -> Option<BTreeMap<BigInt, BigInt>> {
+    let mut result = BTreeMap::new();
+    for class in conjugacy_classes(n, gen)? {
+        *result
+            .entry(class.representative.order())
+            .or_insert_with(|| 0.into()) += class.size;
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groups::mathieu12;
+
+    #[test]
+    fn is_conjugate_test() {
+        // G = A_4
+        let n = 4;
+        let gen = vec![Perm::new(vec![1, 2, 0, 3]), Perm::new(vec![0, 2, 3, 1])];
+        // (0 1 2) and (0 2 1) are conjugate in S_4, but not in A_4.
+        let x = Perm::new(vec![1, 2, 0, 3]);
+        assert!(is_conjugate(n, &gen, &x, &x.inv()).is_none());
+        // (0 1)(2 3) and (0 2)(1 3)
+        let x = Perm::new(vec![1, 0, 3, 2]);
+        let y = Perm::new(vec![2, 3, 0, 1]);
+        let g = is_conjugate(n, &gen, &x, &y).unwrap();
+        assert_eq!(g.inv().compose(&x).compose(&g), y);
+    }
+    #[test]
+    fn conjugacy_classes_test_s5() {
+        let n = 5;
+        let gen = vec![
+            Perm::new(vec![1, 2, 3, 4, 0]),
+            Perm::new(vec![1, 0, 2, 3, 4]),
+        ];
+        let classes = conjugacy_classes(n, &gen).unwrap();
+        // The number of partitions of 5
+        assert_eq!(classes.len(), 7);
+        let sizes: Vec<BigInt> = classes.iter().map(|c| c.size.clone()).collect();
+        let expected: Vec<BigInt> = vec![1, 10, 20, 15, 30, 20, 24]
+            .into_iter()
+            .map(BigInt::from)
+            .collect();
+        // Sorted by cycle types: [1; 5], [1, 1, 1, 2], [1, 1, 3], [1, 2, 2], [1, 4], [2, 3], [5]
+        assert_eq!(sizes, expected);
+    }
+    #[test]
+    fn element_orders_test() {
+        let n = 4;
+        let gen = vec![Perm::new(vec![1, 2, 3, 0]), Perm::new(vec![1, 0, 2, 3])];
+        let expected: BTreeMap<BigInt, BigInt> = [(1, 1), (2, 9), (3, 8), (4, 6)]
+            .into_iter()
+            .map(|(k, v)| (k.into(), v.into()))
+            .collect();
+        assert_eq!(element_orders(n, &gen), Some(expected));
+    }
+    #[test]
+    fn conjugacy_classes_test_m12() {
+        let (n, gen) = mathieu12::generators();
+        let classes = conjugacy_classes(n, &gen).unwrap();
+        assert_eq!(classes.len(), 15);
+        for class in &classes {
+            assert_eq!(&class.size * &class.centralizer_order, 95040.into());
+        }
+    }
+    #[test]
+    fn conjugacy_classes_test_center() {
+        // S_2 x S_4 has 2 * 5 classes, two of which are central.
+        let n = 6;
+        let gen = vec![
+            Perm::new(vec![1, 0, 2, 3, 4, 5]),
+            Perm::new(vec![0, 1, 3, 4, 5, 2]),
+            Perm::new(vec![0, 1, 3, 2, 4, 5]),
+        ];
+        let classes = conjugacy_classes(n, &gen).unwrap();
+        assert_eq!(classes.len(), 10);
+        let central: Vec<&ConjugacyClass> = classes.iter().filter(|c| c.size == 1.into()).collect();
+        assert_eq!(central.len(), 2);
+        // C_2 x M12, whose central involution is 1 of 190080 elements
+        let (n, gen) = mathieu12::generators();
+        let mut gen: Vec<Perm> = gen.iter().map(|g| g.concat(&Perm::e(2))).collect();
+        gen.push(Perm::e(n).concat(&Perm::new(vec![1, 0])));
+        let classes = conjugacy_classes(n + 2, &gen).unwrap();
+        assert_eq!(classes.len(), 30);
+    }
+    #[test]
+    fn conjugacy_classes_test_limit() {
+        // The 15 classes of M12 are not found among 10 samples.
+        let (n, gen) = mathieu12::generators();
+        assert!(sampled_classes(n, &gen, 10).is_none());
+    }
+}
@@ -0,0 +1,261 @@
+use crate::backtrack::orbit_minima;
+use crate::perm::Perm;
+use crate::schreier::incrementally_build_bsgs;
+use crate::subgroup::conjugate;
+use crate::transversal::Transversal;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Returns the lexicographically smallest element of the right coset Gg, where G = \<gen\>.
+///
+/// Permutations are compared by their image lists \[x\[0\], x\[1\], ...\].
+pub fn min_coset_rep(n: usize, gen: &[Perm], g: &Perm) -> Perm {
+    min_coset_rep_in_bsgs(&lex_bsgs(n, gen), g)
+}
+
+/// Returns the lexicographically smallest element of the left coset gG, where G = \<gen\>.
+pub fn min_left_coset_rep(n: usize, gen: &[Perm], g: &Perm) -> Perm {
+    // gG = (g G g^{-1}) g
+    min_coset_rep(n, &conjugate(gen, &g.inv()), g)
+}
+
+/// Returns the lexicographically smallest element of the double coset HgK,
+/// where H = \<h\> and K = \<k\>.
+///
+/// The images of 0, 1, ... are determined in turn, as in canonical_set_image.
+/// After the first i images are determined, every element of HgK with this prefix is
+/// in H^{(i)} y L for one of the current states y, where H^{(i)} fixes 0, ..., i - 1
+/// and L is the pointwise stabilizer in K of the prefix. The next image is the smallest
+/// L-orbit minimum of y\[gamma\] over the states and the points gamma in the orbit of i
+/// under H^{(i)}, and only the states and points attaining it are kept.
+pub fn min_double_coset_rep(n: usize, h: &[Perm], k: &[Perm], g: &Perm) -> Perm {
+    let mut rnd = rand::thread_rng();
+    let h_transversals = lex_bsgs(n, h);
+    let mut states = BTreeSet::new();
+    states.insert(g.clone());
+    let mut stabilizer = k.to_vec();
+    for (i, (_, h_transversal)) in h_transversals.iter().enumerate() {
+        let smallest = orbit_minima(n, &stabilizer);
+        let c = states
+            .iter()
+            .flat_map(|y| {
+                h_transversal
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, repr)| repr.is_some())
+                    .map(|(gamma, _)| smallest[y[gamma]])
+            })
+            .min()
+            .unwrap();
+        let (k_transversals, s) = incrementally_build_bsgs(n, &[c], &stabilizer, &mut rnd);
+        let k_transversal = &k_transversals[0].1;
+        let mut next_states = BTreeSet::new();
+        for y in &states {
+            for (gamma, repr) in h_transversal.iter().enumerate() {
+                // repr maps i to gamma, and x maps y[gamma] to c.
+                if let (Some(repr), Some(x)) = (repr, k_transversal.inverse(y[gamma])) {
+                    let next = repr.compose(y).compose(x);
+                    // States in the same coset of H^{(i + 1)} are merged.
+                    next_states.insert(min_coset_rep_in_bsgs(&h_transversals[i + 1..], &next));
+                }
+            }
+        }
+        states = next_states;
+        stabilizer = s.into_iter().filter(|x| x[c] == c).collect();
+    }
+    states.into_iter().next().unwrap()
+}
+
+/// Returns representatives of the right cosets Hx of H = \<h\> in G = \<gen\>.
+///
+/// The representatives are the lexicographically smallest elements of the cosets,
+/// and the first one is the identity.
+pub fn right_transversal(n: usize, gen: &[Perm], h: &[Perm]) -> Vec<Perm> {
+    right_cosets(n, gen, h).0
+}
+
+/// Returns representatives of the left cosets xH of H = \<h\> in G = \<gen\>.
+///
+/// xH = (H x^{-1})^{-1}, so these are the inverses of the representatives of right_transversal.
+pub fn left_transversal(n: usize, gen: &[Perm], h: &[Perm]) -> Vec<Perm> {
+    right_cosets(n, gen, h).0.iter().map(Perm::inv).collect()
+}
+
+/// Returns the action of G = \<gen\> on the right cosets of H = \<h\>: the i-th element of
+/// the result is the image of gen\[i\], which maps Hx to Hx gen\[i\].
+/// The cosets are numbered as in right_transversal.
+pub fn coset_action(n: usize, gen: &[Perm], h: &[Perm]) -> Vec<Perm> {
+    right_cosets(n, gen, h).1
+}
+
+/// Enumerates the right cosets of H = <h> in G = <gen> by breadth-first search,
+/// identifying each coset by its smallest element.
+fn right_cosets(n: usize, gen: &[Perm], h: &[Perm]) -> (Vec<Perm>, Vec<Perm>) {
+    let h_transversals = lex_bsgs(n, h);
+    let mut reps = vec![Perm::e(n)];
+    let mut index = BTreeMap::new();
+    index.insert(Perm::e(n), 0);
+    let mut images = vec![vec![]; gen.len()];
+    let mut pos = 0;
+    while pos < reps.len() {
+        for (g, image) in gen.iter().zip(&mut images) {
+            let rep = min_coset_rep_in_bsgs(&h_transversals, &reps[pos].compose(g));
+            let next = reps.len();
+            let j = *index.entry(rep.clone()).or_insert(next);
+            if j == next {
+                reps.push(rep);
+            }
+            image.push(j);
+        }
+        pos += 1;
+    }
+    (reps, images.into_iter().map(Perm::new).collect())
+}
+
+/// Returns a BSGS of G = <gen> whose base is 0, 1, ..., n - 1.
+pub(crate) fn lex_bsgs(n: usize, gen: &[Perm]) -> Vec<(usize, Transversal)> {
+    let mut rnd = rand::thread_rng();
+    let base: Vec<usize> = (0..n).collect();
+    let (beta_transversals, _) = incrementally_build_bsgs(n, &base, gen, &mut rnd);
+    beta_transversals
+}
+
+/// The same as min_coset_rep, but G is given by a BSGS whose base is 0, 1, ..., n - 1.
+pub(crate) fn min_coset_rep_in_bsgs(beta_transversals: &[(usize, Transversal)], g: &Perm) -> Perm {
+    // Like strip, but the representative of level i is chosen so that the image of i
+    // is minimized, given the images of 0, ..., i - 1.
+    // (u g)[i] = g[u[i]], so the image of i can be made g[gamma] for any gamma in the orbit.
+    let mut g = g.clone();
+    for (_, transversal) in beta_transversals {
+        let repr = transversal
+            .iter()
+            .enumerate()
+            .filter_map(|(gamma, repr)| repr.as_ref().map(|repr| (g[gamma], repr)))
+            .min_by_key(|&(image, _)| image)
+            .unwrap()
+            .1;
+        g = repr.compose(&g);
+    }
+    g
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::elements;
+    use crate::groups::mathieu12;
+    use crate::schreier::{order, strip};
+
+    #[test]
+    fn min_coset_rep_test() {
+        let n = 3;
+        let gen = vec![Perm::new(vec![1, 0, 2])];
+        let g = Perm::new(vec![2, 1, 0]);
+        // Gg = {(0 2), (0 1 2)}, gG = {(0 2), (0 2 1)}
+        assert_eq!(min_coset_rep(n, &gen, &g), Perm::new(vec![1, 2, 0]));
+        assert_eq!(min_left_coset_rep(n, &gen, &g), Perm::new(vec![2, 0, 1]));
+    }
+    #[test]
+    fn min_coset_rep_test_m12() {
+        let (n, gen) = mathieu12::generators();
+        let mut rnd = rand::thread_rng();
+        let (beta_transversals, _) = incrementally_build_bsgs(n, &[], &gen, &mut rnd);
+        let g = Perm::new(vec![1, 0, 3, 2, 4, 5, 6, 7, 8, 9, 10, 11]);
+        let rep = min_coset_rep(n, &gen, &g);
+        assert!(rep <= g);
+        // rep is in Mg.
+        assert_eq!(
+            strip(&rep.compose(&g.inv()), &beta_transversals).1,
+            Perm::e(n)
+        );
+        // rep does not depend on the choice of the element of the coset.
+        for x in &gen {
+            assert_eq!(min_coset_rep(n, &gen, &x.compose(&g)), rep);
+            assert_eq!(
+                min_left_coset_rep(n, &gen, &g.compose(x)),
+                min_left_coset_rep(n, &gen, &g)
+            );
+        }
+    }
+    #[test]
+    fn transversal_test() {
+        // H = <(0 1 2), (0 1)> = S_3 in S_4
+        let n = 4;
+        let s4 = vec![Perm::new(vec![1, 2, 3, 0]), Perm::new(vec![1, 0, 2, 3])];
+        let h = vec![Perm::new(vec![1, 2, 0, 3]), Perm::new(vec![1, 0, 2, 3])];
+        let mut rnd = rand::thread_rng();
+        let (h_transversals, _) = incrementally_build_bsgs(n, &[], &h, &mut rnd);
+        let in_h = |x: &Perm| strip(x, &h_transversals).1 == Perm::e(n);
+        let right = right_transversal(n, &s4, &h);
+        let left = left_transversal(n, &s4, &h);
+        assert_eq!(right.len(), 4);
+        assert_eq!(right[0], Perm::e(n));
+        for i in 0..4 {
+            for j in 0..i {
+                assert!(!in_h(&right[i].compose(&right[j].inv())));
+                assert!(!in_h(&left[i].inv().compose(&left[j])));
+            }
+        }
+        // The action on the cosets of S_3 is the natural action of S_4.
+        assert_eq!(order(4, &coset_action(n, &s4, &h)), 24.into());
+        // The action on the cosets of the normal subgroup V_4 is S_3.
+        let v4 = vec![Perm::new(vec![1, 0, 3, 2]), Perm::new(vec![2, 3, 0, 1])];
+        let action = coset_action(n, &s4, &v4);
+        assert_eq!(action[0].size(), 6);
+        assert_eq!(order(6, &action), 6.into());
+    }
+    #[test]
+    fn coset_action_test_m12() {
+        // M12 acts on the 12 cosets of the point stabilizer M11.
+        let (n, gen) = mathieu12::generators();
+        let mut rnd = rand::thread_rng();
+        let (_, s) = incrementally_build_bsgs(n, &[0], &gen, &mut rnd);
+        let m11: Vec<Perm> = s.into_iter().filter(|x| x[0] == 0).collect();
+        let action = coset_action(n, &gen, &m11);
+        assert_eq!(action[0].size(), 12);
+        assert_eq!(order(12, &action), 95040.into());
+    }
+    #[test]
+    fn min_double_coset_rep_test() {
+        // H = <(0 1 2 3), (0 2)> = D_8, K = <(0 1 2)>, both in S_4
+        let n = 4;
+        let h = vec![Perm::new(vec![1, 2, 3, 0]), Perm::new(vec![2, 1, 0, 3])];
+        let k = vec![Perm::new(vec![1, 2, 0, 3])];
+        let mut rnd = rand::thread_rng();
+        let (h_transversals, _) = incrementally_build_bsgs(n, &[], &h, &mut rnd);
+        let (k_transversals, _) = incrementally_build_bsgs(n, &[], &k, &mut rnd);
+        let s4 = vec![Perm::new(vec![1, 2, 3, 0]), Perm::new(vec![1, 0, 2, 3])];
+        let (s4_transversals, _) = incrementally_build_bsgs(n, &[], &s4, &mut rnd);
+        for g in elements(n, &s4_transversals) {
+            let mut expected = g.clone();
+            for x in elements(n, &h_transversals) {
+                for y in elements(n, &k_transversals) {
+                    expected = expected.min(x.compose(&g).compose(&y));
+                }
+            }
+            assert_eq!(min_double_coset_rep(n, &h, &k, &g), expected);
+        }
+    }
+    #[test]
+    fn min_double_coset_rep_test_m12() {
+        // H = <(0 1 2 3), (0 2)> = D_8, K = M12, so HgK is the union of the left cosets hgK.
+        let (n, k) = mathieu12::generators();
+        let h = vec![
+            Perm::new(vec![1, 2, 3, 0, 4, 5, 6, 7, 8, 9, 10, 11]),
+            Perm::new(vec![2, 1, 0, 3, 4, 5, 6, 7, 8, 9, 10, 11]),
+        ];
+        let mut rnd = rand::thread_rng();
+        let (h_transversals, _) = incrementally_build_bsgs(n, &[], &h, &mut rnd);
+        let g = Perm::new(vec![5, 11, 0, 7, 2, 9, 1, 10, 3, 6, 4, 8]);
+        let expected = elements(n, &h_transversals)
+            .map(|x| min_left_coset_rep(n, &k, &x.compose(&g)))
+            .min()
+            .unwrap();
+        assert_eq!(min_double_coset_rep(n, &h, &k, &g), expected);
+        for (x, y) in h.iter().zip(&k) {
+            assert_eq!(
+                min_double_coset_rep(n, &h, &k, &x.compose(&g).compose(y)),
+                expected
+            );
+        }
+    }
+}
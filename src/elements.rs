@@ -0,0 +1,173 @@
+use crate::perm::Perm;
+use crate::schreier::strip;
+use crate::transversal::Transversal;
+use num_bigint::BigInt;
+
+// Every element g of G is uniquely written as g = u_{k-1} * ... * u_0, where u_i is
+// the representative of the transversal U_i for the point gamma_i = beta_i^{u_i}.
+// The index of g is the mixed-radix number whose i-th digit is the position of gamma_i
+// in the sorted orbit beta_i^{G^{(i)}}, with the digit of level 0 being the most significant.
+
+/// Returns the sorted orbit of each level.
+fn sorted_orbits(beta_transversals: &[(usize, Transversal)]) -> Vec<Vec<usize>> {
+    beta_transversals
+        .iter()
+        .map(|(_, transversal)| {
+            (0..transversal.len())
+                .filter(|&x| transversal[x].is_some())
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the element of index `index` of the group whose BSGS is beta_transversals,
+/// or None if index >= |G|.
+pub fn element_at(
+    n: usize,
+    beta_transversals: &[(usize, Transversal)],
+    index: &BigInt,
+) -> Option<Perm> {
+    if *index < 0.into() {
+        return None;
+    }
+    let orbits = sorted_orbits(beta_transversals);
+    let mut digits = vec![0; orbits.len()];
+    let mut index = index.clone();
+    for (digit, orbit) in digits.iter_mut().zip(&orbits).rev() {
+        let len = BigInt::from(orbit.len());
+        *digit = (&index % &len).try_into().unwrap();
+        index /= len;
+    }
+    if index != 0.into() {
+        return None;
+    }
+    let mut g = Perm::e(n);
+    for ((_, transversal), (&digit, orbit)) in
+        beta_transversals.iter().zip(digits.iter().zip(&orbits))
+    {
+        g = transversal[orbit[digit]].as_ref().unwrap().compose(&g);
+    }
+    Some(g)
+}
+
+/// Returns the index of g in the group whose BSGS is beta_transversals,
+/// or None if g is not in the group. This is the inverse of element_at.
+pub fn index_of(beta_transversals: &[(usize, Transversal)], g: &Perm) -> Option<BigInt> {
+    let (us, rest) = strip(g, beta_transversals);
+    if rest != Perm::e(g.size()) {
+        return None;
+    }
+    let mut index = BigInt::from(0);
+    for ((beta, transversal), u) in beta_transversals.iter().zip(&us) {
+        let gamma = u[*beta];
+        let digit = transversal[..gamma].iter().filter(|x| x.is_some()).count();
+        let len = transversal.iter().filter(|x| x.is_some()).count();
+        index = index * len + digit;
+    }
+    Some(index)
+}
+
+/// Returns an iterator over all elements of the group whose BSGS is beta_transversals,
+/// in ascending order of their indices.
+pub fn elements(n: usize, beta_transversals: &[(usize, Transversal)]) -> Elements<'_> {
+    let orbits = sorted_orbits(beta_transversals);
+    let k = orbits.len();
+    let mut result = Elements {
+        beta_transversals,
+        orbits,
+        digits: vec![0; k],
+        partial: vec![Perm::e(n); k + 1],
+        done: false,
+    };
+    result.update(0);
+    result
+}
+
+pub struct Elements<'a> {
+    beta_transversals: &'a [(usize, Transversal)],
+    orbits: Vec<Vec<usize>>,
+    digits: Vec<usize>,
+    /// partial\[i\] = u_{i-1} * ... * u_0 for the current digits
+    partial: Vec<Perm>,
+    done: bool,
+}
+
+impl Elements<'_> {
+    /// Recomputes partial\[i + 1..\] after the digits of levels i.. have changed.
+    fn update(&mut self, i: usize) {
+        for j in i..self.digits.len() {
+            let gamma = self.orbits[j][self.digits[j]];
+            let u = self.beta_transversals[j].1[gamma].as_ref().unwrap();
+            self.partial[j + 1] = u.compose(&self.partial[j]);
+        }
+    }
+}
+
+impl Iterator for Elements<'_> {
+    type Item = Perm;
+
+    fn next(&mut self) -> Option<Perm> {
+        if self.done {
+            return None;
+        }
+        let result = self.partial[self.digits.len()].clone();
+        // Increments the digits, the last level being the least significant.
+        let mut i = self.digits.len();
+        loop {
+            if i == 0 {
+                self.done = true;
+                break;
+            }
+            i -= 1;
+            self.digits[i] += 1;
+            if self.digits[i] < self.orbits[i].len() {
+                self.update(i);
+                break;
+            }
+            self.digits[i] = 0;
+        }
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groups::rubik;
+    use crate::schreier::{bsgs_order, incrementally_build_bsgs, random_element};
+
+    #[test]
+    fn elements_test_s4() {
+        let n = 4;
+        let gen = vec![Perm::new(vec![1, 2, 3, 0]), Perm::new(vec![1, 0, 2, 3])];
+        let mut rnd = rand::thread_rng();
+        let (beta_transversals, _) = incrementally_build_bsgs(n, &[], &gen, &mut rnd);
+        let all: Vec<Perm> = elements(n, &beta_transversals).collect();
+        assert_eq!(all.len(), 24);
+        for (i, g) in all.iter().enumerate() {
+            let i = BigInt::from(i);
+            assert_eq!(element_at(n, &beta_transversals, &i).as_ref(), Some(g));
+            assert_eq!(index_of(&beta_transversals, g), Some(i));
+        }
+        assert_eq!(element_at(n, &beta_transversals, &24.into()), None);
+        // V_4 does not contain (0 1).
+        let v4 = vec![Perm::new(vec![1, 0, 3, 2]), Perm::new(vec![2, 3, 0, 1])];
+        let (beta_transversals, _) = incrementally_build_bsgs(n, &[], &v4, &mut rnd);
+        assert_eq!(elements(n, &beta_transversals).count(), 4);
+        assert_eq!(index_of(&beta_transversals, &gen[1]), None);
+    }
+    #[test]
+    fn index_of_test_rubik() {
+        let (n, gen) = rubik::generators();
+        let mut rnd = rand::thread_rng();
+        let (beta_transversals, _) = incrementally_build_bsgs(n, &[], &gen, &mut rnd);
+        let order = bsgs_order(&beta_transversals);
+        for _ in 0..100 {
+            let g = random_element(n, &beta_transversals, &mut rnd);
+            let index = index_of(&beta_transversals, &g).unwrap();
+            assert!(index < order);
+            assert_eq!(element_at(n, &beta_transversals, &index), Some(g));
+        }
+        assert!(element_at(n, &beta_transversals, &(order - 1)).is_some());
+    }
+}
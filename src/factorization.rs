@@ -0,0 +1,295 @@
+use crate::fpgroup::{free_reduce, inverse, Word};
+use crate::perm::Perm;
+use crate::schreier::{bsgs_order, incrementally_build_bsgs, strip};
+use crate::transversal::Transversal;
+use rand::Rng;
+
+// Reference: T. Minkwitz, An Algorithm for Solving the Factorization Problem in
+// Permutation Groups, J. Symbolic Comput. 26 (1998), 89-95.
+
+/// The length of the random words sifted into the table
+const WALK_LENGTH: usize = 32;
+/// Words longer than this are not sifted further.
+const MAX_LENGTH: usize = 1000;
+/// The number of random words sifted between two improvement rounds
+const WALKS_PER_ROUND: usize = 64;
+/// The number of improvement rounds run after the table is complete
+const EXTRA_ROUNDS: usize = 2;
+/// The number of rounds after which the missing entries are filled deterministically
+const MAX_ROUNDS: usize = 20;
+
+/// table\[gamma\] = (u, w): u maps the base point of the level to gamma, and w is a word for u.
+type Table = Vec<Option<(Perm, Word)>>;
+
+/// A stabilizer chain of G = \<gen\> whose transversal elements are kept as short words
+/// in gen, so that elements of G can be written as words in gen.
+pub struct Factorizer {
+    n: usize,
+    /// (beta_i, table_i), where the entries of table_i are in G^(i)
+    levels: Vec<(usize, Table)>,
+}
+
+impl Factorizer {
+    /// Builds the table by Minkwitz's algorithm. Elements given by random words are sifted
+    /// through the table, and an element replaces an entry with a longer word.
+    /// Products of entries of the same level are sifted as well, which fills and
+    /// shortens the table quickly. Entries still missing after MAX_ROUNDS rounds,
+    /// which happens when only long words reach a level, are filled by complete.
+    pub fn new(n: usize, gen: &[Perm]) -> Self {
+        let mut rnd = rand::thread_rng();
+        let (beta_transversals, _) = incrementally_build_bsgs(n, &[], gen, &mut rnd);
+        let mut factorizer = Factorizer::empty(n, &beta_transversals);
+        let orbit_lengths: Vec<usize> = beta_transversals
+            .iter()
+            .map(|(_, transversal)| transversal.iter().filter(|x| x.is_some()).count())
+            .collect();
+        let is_complete = |f: &Factorizer| {
+            f.levels
+                .iter()
+                .zip(&orbit_lengths)
+                .all(|((_, table), &len)| table.iter().filter(|x| x.is_some()).count() == len)
+        };
+        for (a, g) in gen.iter().enumerate() {
+            factorizer.sift_into(0, g.clone(), vec![a as i32 + 1]);
+        }
+        let mut rounds = 0;
+        let mut extra_rounds = 0;
+        while extra_rounds < EXTRA_ROUNDS && rounds < MAX_ROUNDS {
+            if gen.is_empty() {
+                break;
+            }
+            rounds += 1;
+            for _ in 0..WALKS_PER_ROUND {
+                let mut g = Perm::e(n);
+                let mut word = vec![];
+                for _ in 0..WALK_LENGTH {
+                    let a = rnd.gen_range(0..gen.len());
+                    let letter = if rnd.gen_bool(0.5) { 1 } else { -1 } * (a as i32 + 1);
+                    g = g.compose(&if letter > 0 {
+                        gen[a].clone()
+                    } else {
+                        gen[a].inv()
+                    });
+                    word = free_reduce(&[&word[..], &[letter]].concat());
+                    factorizer.sift_into(0, g.clone(), word.clone());
+                }
+            }
+            factorizer.improve();
+            if is_complete(&factorizer) {
+                extra_rounds += 1;
+            }
+        }
+        if !is_complete(&factorizer) {
+            factorizer.complete(gen, &beta_transversals);
+        }
+        factorizer
+    }
+
+    /// Returns the table whose only entries are the identity at the base points.
+    fn empty(n: usize, beta_transversals: &[(usize, Transversal)]) -> Self {
+        Factorizer {
+            n,
+            levels: beta_transversals
+                .iter()
+                .map(|&(beta, _)| {
+                    let mut table = vec![None; n];
+                    table[beta] = Some((Perm::e(n), vec![]));
+                    (beta, table)
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns a word w in gen with g = w (see fpgroup::evaluate_word),
+    /// or None if g is not in G.
+    pub fn factorize(&self, g: &Perm) -> Option<Word> {
+        // g = u_{k-1} ... u_0, where u_i is an entry of level i
+        let mut g = g.clone();
+        let mut words = vec![];
+        for (beta, table) in &self.levels {
+            let (u, word) = table[g[*beta]].as_ref()?;
+            g = g.compose(&u.inv());
+            words.push(&word[..]);
+        }
+        if g != Perm::e(self.n) {
+            return None;
+        }
+        words.reverse();
+        Some(free_reduce(&words.concat()))
+    }
+
+    /// Sifts g = word in G^(start) through the levels from start, storing an element
+    /// wherever the table has no entry or a longer word.
+    fn sift_into(&mut self, start: usize, mut g: Perm, mut word: Word) {
+        let e = Perm::e(self.n);
+        for (beta, table) in self.levels[start..].iter_mut() {
+            if g == e || word.len() > MAX_LENGTH {
+                return;
+            }
+            let gamma = g[*beta];
+            let replace = match &table[gamma] {
+                None => true,
+                Some((_, w)) => w.len() > word.len(),
+            };
+            if replace {
+                // The inverse is stored as well.
+                let g_inv = g.inv();
+                let delta = g_inv[*beta];
+                if table[delta]
+                    .as_ref()
+                    .is_none_or(|(_, w)| w.len() > word.len())
+                {
+                    table[delta] = Some((g_inv, inverse(&word)));
+                }
+                match table[gamma].replace((g, word)) {
+                    None => return,
+                    // The old entry is sifted further.
+                    Some((old, old_word)) => {
+                        g = old;
+                        word = old_word;
+                    }
+                }
+            }
+            let (u, w) = table[gamma].as_ref().unwrap();
+            g = g.compose(&u.inv());
+            word = free_reduce(&[&word[..], &inverse(w)].concat());
+        }
+    }
+
+    /// Fills the missing entries of every level i by the orbit of beta_i under words for
+    /// generators of G^(i). These are gen for G^(0), and the entries of the levels from i
+    /// for i > 0, together with Schreier generators u_gamma s u_{gamma^s}^{-1} of level i - 1
+    /// added until they generate G^(i), which they do by Schreier's lemma.
+    fn complete(&mut self, gen: &[Perm], beta_transversals: &[(usize, Transversal)]) {
+        let n = self.n;
+        let mut rnd = rand::thread_rng();
+        let mut gens: Vec<(Perm, Word)> = gen
+            .iter()
+            .enumerate()
+            .map(|(a, g)| (g.clone(), vec![a as i32 + 1]))
+            .collect();
+        for i in 0..self.levels.len() {
+            let (beta, table) = &mut self.levels[i];
+            let mut queue: Vec<usize> = (0..n).filter(|&x| table[x].is_some()).collect();
+            while let Some(gamma) = queue.pop() {
+                for (s, w) in &gens {
+                    let delta = s[gamma];
+                    if table[delta].is_none() {
+                        let (u, u_word) = table[gamma].as_ref().unwrap();
+                        let word = free_reduce(&[&u_word[..], &w[..]].concat());
+                        table[delta] = Some((u.compose(s), word));
+                        queue.push(delta);
+                    }
+                }
+            }
+            debug_assert!(table[*beta].is_some());
+            if i + 1 == self.levels.len() {
+                break;
+            }
+            let target = bsgs_order(&beta_transversals[i + 1..]);
+            let mut next: Vec<(Perm, Word)> = self.levels[i + 1..]
+                .iter()
+                .flat_map(|(_, table)| table.iter().flatten().cloned())
+                .filter(|(u, _)| *u != Perm::e(n))
+                .collect();
+            let perms: Vec<Perm> = next.iter().map(|(u, _)| u.clone()).collect();
+            let (mut transversals, mut strong) = incrementally_build_bsgs(n, &[], &perms, &mut rnd);
+            let table = &self.levels[i].1;
+            'schreier: for (u, u_word) in table.iter().flatten() {
+                for (s, w) in &gens {
+                    if bsgs_order(&transversals) == target {
+                        break 'schreier;
+                    }
+                    let (v, v_word) = table[u.compose(s)[self.levels[i].0]].as_ref().unwrap();
+                    let y = u.compose(s).compose(&v.inv());
+                    if strip(&y, &transversals).1 == Perm::e(n) {
+                        continue;
+                    }
+                    let word = free_reduce(&[&u_word[..], &w[..], &inverse(v_word)].concat());
+                    next.push((y.clone(), word));
+                    strong.push(y);
+                    let base: Vec<usize> = transversals.iter().map(|&(beta, _)| beta).collect();
+                    (transversals, strong) = incrementally_build_bsgs(n, &base, &strong, &mut rnd);
+                }
+            }
+            gens = next;
+        }
+    }
+
+    /// Sifts the products of pairs of entries of each level.
+    fn improve(&mut self) {
+        for i in 0..self.levels.len() {
+            let entries: Vec<(Perm, Word)> = self.levels[i].1.iter().flatten().cloned().collect();
+            for (x, x_word) in &entries {
+                for (y, y_word) in &entries {
+                    let word = free_reduce(&[&x_word[..], &y_word[..]].concat());
+                    self.sift_into(i, x.compose(y), word);
+                }
+            }
+        }
+    }
+}
+
+/// Returns a word w in gen with g = w (see fpgroup::evaluate_word),
+/// or None if g is not in G = \<gen\>.
+pub fn factorization(n: usize, gen: &[Perm], g: &Perm) -> Option<Word> {
+    Factorizer::new(n, gen).factorize(g)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fpgroup::evaluate_word;
+    use crate::groups::{mathieu12, rubik};
+    use crate::schreier::random_element;
+
+    #[test]
+    fn factorization_test() {
+        // (0 1) is not in A_4.
+        let n = 4;
+        let a4 = vec![Perm::new(vec![1, 2, 0, 3]), Perm::new(vec![0, 2, 3, 1])];
+        assert!(factorization(n, &a4, &Perm::new(vec![1, 0, 2, 3])).is_none());
+        let g = Perm::new(vec![1, 0, 3, 2]);
+        assert_eq!(
+            evaluate_word(n, &a4, &factorization(n, &a4, &g).unwrap()),
+            g
+        );
+        let (n, gen) = mathieu12::generators();
+        let g = gen[0].compose(&gen[1]).compose(&gen[0]).pow(3);
+        assert_eq!(
+            evaluate_word(n, &gen, &factorization(n, &gen, &g).unwrap()),
+            g
+        );
+    }
+    #[test]
+    fn factorization_test_complete() {
+        // An empty table is filled by complete alone.
+        let (n, gen) = mathieu12::generators();
+        let mut rnd = rand::thread_rng();
+        let (beta_transversals, _) = incrementally_build_bsgs(n, &[], &gen, &mut rnd);
+        let mut factorizer = Factorizer::empty(n, &beta_transversals);
+        factorizer.complete(&gen, &beta_transversals);
+        for ((_, table), (_, transversal)) in factorizer.levels.iter().zip(&beta_transversals) {
+            for (entry, repr) in table.iter().zip(transversal.iter()) {
+                assert_eq!(entry.is_some(), repr.is_some());
+            }
+        }
+        for _ in 0..10 {
+            let g = random_element(n, &beta_transversals, &mut rnd);
+            let word = factorizer.factorize(&g).unwrap();
+            assert_eq!(evaluate_word(n, &gen, &word), g);
+        }
+    }
+    #[test]
+    fn factorization_test_rubik() {
+        let (n, gen) = rubik::generators();
+        let factorizer = Factorizer::new(n, &gen);
+        let mut rnd = rand::thread_rng();
+        let (beta_transversals, _) = incrementally_build_bsgs(n, &[], &gen, &mut rnd);
+        for _ in 0..10 {
+            let g = random_element(n, &beta_transversals, &mut rnd);
+            let word = factorizer.factorize(&g).unwrap();
+            assert_eq!(evaluate_word(n, &gen, &word), g);
+        }
+    }
+}
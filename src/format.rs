@@ -0,0 +1,151 @@
+use crate::perm::Perm;
+
+/// Parses a permutation of 0-based points, either in cycle notation such as "(0 1 2)(3 4)"
+/// or as the list of images such as "[1, 2, 0, 4, 3]" (the brackets are optional).
+/// Points in a cycle are separated by spaces or commas, and "()" is the identity.
+///
+/// Returns the images, which may be shorter than the degree of the group.
+pub fn parse_images(s: &str) -> Result<Vec<usize>, String> {
+    let s = s.trim();
+    if s.starts_with('(') {
+        let mut cycles = vec![];
+        let mut rest = s;
+        while !rest.is_empty() {
+            let Some(body) = rest.strip_prefix('(') else {
+                return Err(format!("expected '(' in {:?}", s));
+            };
+            let Some(end) = body.find(')') else {
+                return Err(format!("unclosed cycle in {:?}", s));
+            };
+            cycles.push(parse_points(&body[..end])?);
+            rest = body[end + 1..].trim_start();
+        }
+        let degree = cycles.iter().flatten().map(|&x| x + 1).max().unwrap_or(0);
+        let mut images: Vec<usize> = (0..degree).collect();
+        let mut seen = vec![false; degree];
+        for cycle in &cycles {
+            for (i, &x) in cycle.iter().enumerate() {
+                if seen[x] {
+                    return Err(format!("point {} appears twice in {:?}", x, s));
+                }
+                seen[x] = true;
+                images[x] = cycle[(i + 1) % cycle.len()];
+            }
+        }
+        return Ok(images);
+    }
+    let body = s.strip_prefix('[').unwrap_or(s);
+    let body = body.strip_suffix(']').unwrap_or(body);
+    let images = parse_points(body)?;
+    let mut seen = vec![false; images.len()];
+    for &x in &images {
+        if x >= images.len() || seen[x] {
+            return Err(format!("{:?} is not a permutation", s));
+        }
+        seen[x] = true;
+    }
+    Ok(images)
+}
+
+/// Parses a permutation of degree n. See parse_images for the syntax.
+pub fn parse_perm(n: usize, s: &str) -> Result<Perm, String> {
+    let mut images = parse_images(s)?;
+    if images.len() > n {
+        return Err(format!("{:?} moves points beyond the degree {}", s, n));
+    }
+    images.extend(images.len()..n);
+    Ok(Perm::new(images))
+}
+
+/// Parses generators given one per line. Empty lines and lines starting with '#' are ignored.
+/// The degree is the largest degree of the generators, unless a larger one is given.
+pub fn parse_generators(text: &str, degree: Option<usize>) -> Result<(usize, Vec<Perm>), String> {
+    let mut gen_images = vec![];
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        gen_images.push(parse_images(line)?);
+    }
+    let max_degree = gen_images.iter().map(|x| x.len()).max().unwrap_or(0);
+    let n = match degree {
+        Some(n) if n < max_degree => {
+            return Err(format!("a generator moves points beyond the degree {}", n));
+        }
+        Some(n) => n,
+        None => max_degree,
+    };
+    let gen = gen_images
+        .into_iter()
+        .map(|mut images| {
+            images.extend(images.len()..n);
+            Perm::new(images)
+        })
+        .collect();
+    Ok((n, gen))
+}
+
+/// Returns the cycle notation of g, omitting fixed points. The identity is "()".
+pub fn to_cycles(g: &Perm) -> String {
+    let n = g.size();
+    let mut visited = vec![false; n];
+    let mut result = String::new();
+    for i in 0..n {
+        if visited[i] || g[i] == i {
+            continue;
+        }
+        let mut cycle = vec![];
+        let mut j = i;
+        while !visited[j] {
+            visited[j] = true;
+            cycle.push(j.to_string());
+            j = g[j];
+        }
+        result += &format!("({})", cycle.join(" "));
+    }
+    if result.is_empty() {
+        result += "()";
+    }
+    result
+}
+
+fn parse_points(s: &str) -> Result<Vec<usize>, String> {
+    s.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|x| !x.is_empty())
+        .map(|x| x.parse().map_err(|_| format!("invalid point {:?}", x)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_perm_test() {
+        let g = Perm::new(vec![1, 2, 0, 4, 3]);
+        assert_eq!(parse_perm(5, "(0 1 2)(3 4)"), Ok(g.clone()));
+        assert_eq!(parse_perm(5, "(0,1,2) (3,4)"), Ok(g.clone()));
+        assert_eq!(parse_perm(5, "[1, 2, 0, 4, 3]"), Ok(g.clone()));
+        assert_eq!(parse_perm(5, "1 2 0"), Ok(Perm::new(vec![1, 2, 0, 3, 4])));
+        assert_eq!(parse_perm(3, "()"), Ok(Perm::e(3)));
+        assert_eq!(to_cycles(&g), "(0 1 2)(3 4)");
+        assert_eq!(to_cycles(&Perm::e(3)), "()");
+        assert!(parse_perm(5, "(0 1)(1 2)").is_err());
+        assert!(parse_perm(5, "[0, 0, 1]").is_err());
+        assert!(parse_perm(2, "(0 1 2)").is_err());
+        assert!(parse_perm(5, "(0 1").is_err());
+    }
+    #[test]
+    fn parse_generators_test() {
+        let text = "# S_4\n(0 1 2 3)\n\n[1, 0]\n";
+        let (n, gen) = parse_generators(text, None).unwrap();
+        assert_eq!(n, 4);
+        assert_eq!(
+            gen,
+            vec![Perm::new(vec![1, 2, 3, 0]), Perm::new(vec![1, 0, 2, 3])]
+        );
+        assert_eq!(parse_generators(text, Some(6)).unwrap().0, 6);
+        assert!(parse_generators(text, Some(3)).is_err());
+    }
+}
@@ -0,0 +1,293 @@
+use crate::perm::Perm;
+use num_bigint::BigInt;
+
+/// A word in the generators: the letter i + 1 stands for the i-th generator x_i,
+/// and -(i + 1) for its inverse. The empty word is the identity.
+pub type Word = Vec<i32>;
+
+/// A finitely presented group <x_0, ..., x_{k-1} | relators>.
+#[derive(Clone, Debug)]
+pub struct FpGroup {
+    pub generators: usize,
+    pub relators: Vec<Word>,
+}
+
+impl FpGroup {
+    pub fn new(generators: usize, relators: Vec<Word>) -> Self {
+        for &letter in relators.iter().flatten() {
+            assert!(letter != 0 && letter.unsigned_abs() as usize <= generators);
+        }
+        FpGroup {
+            generators,
+            relators,
+        }
+    }
+
+    /// Returns the action of the generators on the right cosets of the subgroup generated
+    /// by `subgroup`, as (the number of cosets, the images of the generators).
+    /// The coset of the subgroup itself is 0.
+    /// Returns None if the enumeration needs more than max_cosets cosets.
+    pub fn coset_action(&self, subgroup: &[Word], max_cosets: usize) -> Option<(usize, Vec<Perm>)> {
+        let mut table = CosetTable::new(2 * self.generators, max_cosets);
+        for w in subgroup {
+            table.scan_and_fill(0, w)?;
+        }
+        // HLT strategy: every relator is scanned at every coset in order of definition,
+        // and the row is filled afterwards.
+        let mut c = 0;
+        while c < table.rows.len() {
+            for r in &self.relators {
+                if !table.is_live(c) {
+                    break;
+                }
+                table.scan_and_fill(c, r)?;
+            }
+            if table.is_live(c) {
+                for x in 0..2 * self.generators {
+                    if table.rows[c][x] == UNDEF {
+                        table.define(c, x)?;
+                    }
+                }
+            }
+            c += 1;
+        }
+        Some(table.into_permutations(self.generators))
+    }
+
+    /// Returns the regular permutation representation, the action on the cosets of
+    /// the trivial subgroup, or None if the group has more than max_cosets elements.
+    pub fn permutation_representation(&self, max_cosets: usize) -> Option<(usize, Vec<Perm>)> {
+        self.coset_action(&[], max_cosets)
+    }
+
+    /// Returns the order of the group, or None if it is larger than max_cosets.
+    pub fn order(&self, max_cosets: usize) -> Option<BigInt> {
+        self.permutation_representation(max_cosets)
+            .map(|(n, _)| n.into())
+    }
+}
+
+/// Returns the image of a word under x_i |-> gen\[i\].
+pub fn evaluate_word(n: usize, gen: &[Perm], word: &[i32]) -> Perm {
+    let mut result = Perm::e(n);
+    for &letter in word {
+        let g = &gen[letter.unsigned_abs() as usize - 1];
+        result = result.compose(&if letter > 0 { g.clone() } else { g.inv() });
+    }
+    result
+}
+
+/// Returns the inverse of a word.
+pub(crate) fn inverse(word: &[i32]) -> Word {
+    word.iter().rev().map(|&x| -x).collect()
+}
+
+/// Cancels adjacent pairs of inverse letters.
+pub(crate) fn free_reduce(word: &[i32]) -> Word {
+    let mut result: Word = vec![];
+    for &x in word {
+        if result.last() == Some(&-x) {
+            result.pop();
+        } else {
+            result.push(x);
+        }
+    }
+    result
+}
+
+const UNDEF: usize = usize::MAX;
+
+// Reference: D. F. Holt, B. Eick, E. A. O'Brien, Handbook of Computational Group Theory,
+// Section 5.1.
+struct CosetTable {
+    /// rows\[c\]\[x\] = c^x, where the column of x_i is 2i and that of x_i^{-1} is 2i + 1
+    rows: Vec<Vec<usize>>,
+    /// Union-find forest of coincident cosets. Live cosets are the roots.
+    parent: Vec<usize>,
+    columns: usize,
+    max_cosets: usize,
+}
+
+fn column(letter: i32) -> usize {
+    2 * (letter.unsigned_abs() as usize - 1) + (letter < 0) as usize
+}
+
+impl CosetTable {
+    fn new(columns: usize, max_cosets: usize) -> Self {
+        CosetTable {
+            rows: vec![vec![UNDEF; columns]],
+            parent: vec![0],
+            columns,
+            max_cosets,
+        }
+    }
+
+    fn is_live(&self, c: usize) -> bool {
+        self.parent[c] == c
+    }
+
+    /// Defines c^x as a new coset.
+    fn define(&mut self, c: usize, x: usize) -> Option<()> {
+        if self.rows.len() >= self.max_cosets {
+            return None;
+        }
+        let d = self.rows.len();
+        self.rows.push(vec![UNDEF; self.columns]);
+        self.parent.push(d);
+        self.rows[c][x] = d;
+        self.rows[d][x ^ 1] = c;
+        Some(())
+    }
+
+    /// Traces the word w from c forwards and backwards, defining new cosets
+    /// until w is a closed loop at c.
+    fn scan_and_fill(&mut self, c: usize, w: &[i32]) -> Option<()> {
+        let (mut f, mut b) = (c, c);
+        let (mut i, mut j) = (0, w.len());
+        loop {
+            // w[..i] leads from c to f, and w[j..] leads from b to c.
+            while i < j && self.rows[f][column(w[i])] != UNDEF {
+                f = self.rows[f][column(w[i])];
+                i += 1;
+            }
+            if i == j {
+                if f != b {
+                    self.coincidence(f, b);
+                }
+                return Some(());
+            }
+            while j > i && self.rows[b][column(w[j - 1]) ^ 1] != UNDEF {
+                b = self.rows[b][column(w[j - 1]) ^ 1];
+                j -= 1;
+            }
+            if i == j {
+                self.coincidence(f, b);
+                return Some(());
+            }
+            if i + 1 == j {
+                // Deduction
+                let x = column(w[i]);
+                self.rows[f][x] = b;
+                self.rows[b][x ^ 1] = f;
+                return Some(());
+            }
+            self.define(f, column(w[i]))?;
+        }
+    }
+
+    fn find(&mut self, c: usize) -> usize {
+        let mut root = c;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut c = c;
+        while self.parent[c] != root {
+            let next = self.parent[c];
+            self.parent[c] = root;
+            c = next;
+        }
+        root
+    }
+
+    /// Merges the classes of a and b, keeping the smaller root.
+    fn merge(&mut self, a: usize, b: usize, queue: &mut Vec<usize>) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        let (a, b) = if a < b { (a, b) } else { (b, a) };
+        self.parent[b] = a;
+        queue.push(b);
+    }
+
+    /// Processes the coincidence of a and b and all its consequences.
+    fn coincidence(&mut self, a: usize, b: usize) {
+        let mut queue = vec![];
+        self.merge(a, b, &mut queue);
+        let mut pos = 0;
+        while pos < queue.len() {
+            let e = queue[pos];
+            pos += 1;
+            for x in 0..self.columns {
+                let f = self.rows[e][x];
+                if f == UNDEF {
+                    continue;
+                }
+                self.rows[f][x ^ 1] = UNDEF;
+                let (e1, f1) = (self.find(e), self.find(f));
+                if self.rows[e1][x] != UNDEF {
+                    let g = self.rows[e1][x];
+                    self.merge(f1, g, &mut queue);
+                } else if self.rows[f1][x ^ 1] != UNDEF {
+                    let g = self.rows[f1][x ^ 1];
+                    self.merge(e1, g, &mut queue);
+                } else {
+                    self.rows[e1][x] = f1;
+                    self.rows[f1][x ^ 1] = e1;
+                }
+            }
+        }
+    }
+
+    /// Renumbers the live cosets and returns the permutations of the generators.
+    fn into_permutations(mut self, generators: usize) -> (usize, Vec<Perm>) {
+        let live: Vec<usize> = (0..self.rows.len()).filter(|&c| self.is_live(c)).collect();
+        let mut index = vec![UNDEF; self.rows.len()];
+        for (i, &c) in live.iter().enumerate() {
+            index[c] = i;
+        }
+        let mut perms = vec![];
+        for i in 0..generators {
+            let image = live
+                .iter()
+                .map(|&c| index[self.find(self.rows[c][2 * i])])
+                .collect();
+            perms.push(Perm::new(image));
+        }
+        (live.len(), perms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schreier::order;
+
+    #[test]
+    fn coset_action_test_a5() {
+        // A_5 = <a, b | a^2, b^3, (ab)^5>
+        let g = FpGroup::new(2, vec![vec![1, 1], vec![2, 2, 2], [1, 2].repeat(5)]);
+        assert_eq!(g.order(1000), Some(60.into()));
+        // The action on the cosets of <b>
+        let (n, gen) = g.coset_action(&[vec![2]], 1000).unwrap();
+        assert_eq!(n, 20);
+        assert_eq!(order(n, &gen), 60.into());
+        for r in &g.relators {
+            assert_eq!(evaluate_word(n, &gen, r), Perm::e(n));
+        }
+    }
+    #[test]
+    fn order_test() {
+        // S_3 = <a, b | a^2, b^3, (ab)^2>
+        let g = FpGroup::new(2, vec![vec![1, 1], vec![2, 2, 2], vec![1, 2, 1, 2]]);
+        assert_eq!(g.order(100), Some(6.into()));
+        // PSL(2, 7) = <a, b | a^2, b^3, (ab)^7, [a, b]^4>
+        let g = FpGroup::new(
+            2,
+            vec![
+                vec![1, 1],
+                vec![2, 2, 2],
+                [1, 2].repeat(7),
+                [-1, -2, 1, 2].repeat(4),
+            ],
+        );
+        let (n, gen) = g.permutation_representation(1000).unwrap();
+        assert_eq!(order(n, &gen), 168.into());
+        // <a | a^-3 a^5> = C_2, <a, b | a b a^-1 b^-1> = Z^2 is infinite.
+        assert_eq!(
+            FpGroup::new(1, vec![vec![-1, -1, -1, 1, 1, 1, 1, 1]]).order(10),
+            Some(2.into())
+        );
+        assert_eq!(FpGroup::new(2, vec![vec![1, 2, -1, -2]]).order(1000), None);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::perm::Perm;
+
+/// Parses the permutations in GAP syntax occurring in text, in order of appearance.
+/// Points are 1-based, as in GAP, and are converted to 0-based ones.
+///
+/// A permutation is a product of cycles such as (1,4)(3,10), and () is the identity.
+/// The permutations may be separated by whitespace, ',', ';', '[', ']', `Group(...)`
+/// and assignments `name :=`, so that all of
+/// `Group((1,4)(3,10), (1,8,9)(2,3,4))`, `[ (1,4)(3,10), (1,8,9)(2,3,4) ]` and
+/// `b11 := (1,4)(3,10);` are accepted. Anything else, such as the operators in
+/// `(1,2)*(2,3)` or `(1,2)^2`, is an error. Comments starting with '#' and
+/// line continuations (a backslash at the end of a line) are removed first.
+///
+/// The degree is the largest point moved, unless a larger one is given.
+pub fn parse_gap(text: &str, degree: Option<usize>) -> Result<(usize, Vec<Perm>), String> {
+    let text: String = text
+        .lines()
+        .map(|line| line.split('#').next().unwrap())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .replace("\\\n", "");
+    let bytes = text.as_bytes();
+    let mut perms: Vec<Vec<Vec<usize>>> = vec![];
+    // Whether the last token was a cycle, so that the next cycle belongs to the same permutation
+    let mut in_perm = false;
+    // The number of unclosed `Group(`
+    let mut depth = 0;
+    let mut pos = 0;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            c if c.is_ascii_whitespace() => {
+                pos += 1;
+                continue;
+            }
+            b'(' => {
+                let Some((cycle, end)) = parse_cycle(&text, pos)? else {
+                    return Err(format!("invalid cycle at {:?}", &text[pos..]));
+                };
+                if !in_perm {
+                    perms.push(vec![]);
+                }
+                perms.last_mut().unwrap().push(cycle);
+                in_perm = true;
+                pos = end;
+                continue;
+            }
+            b',' | b';' | b'[' | b']' => pos += 1,
+            b')' if depth > 0 => {
+                depth -= 1;
+                pos += 1;
+            }
+            b':' if bytes.get(pos + 1) == Some(&b'=') => pos += 2,
+            c if c.is_ascii_alphabetic() || c == b'_' => {
+                let len = text[pos..]
+                    .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                    .unwrap_or(text.len() - pos);
+                let name = &text[pos..pos + len];
+                pos += len;
+                let rest = text[pos..].trim_start();
+                if name == "Group" && rest.starts_with('(') {
+                    depth += 1;
+                    pos = text.len() - rest.len() + 1;
+                } else if !rest.starts_with(":=") {
+                    return Err(format!("unexpected {:?}", name));
+                }
+            }
+            _ => {
+                let c = text[pos..].chars().next().unwrap();
+                return Err(format!("unexpected {:?}", c));
+            }
+        }
+        in_perm = false;
+    }
+    let max_degree = perms.iter().flatten().flatten().max().map_or(0, |&x| x + 1);
+    let n = match degree {
+        Some(n) if n < max_degree => {
+            return Err(format!("a generator moves points beyond the degree {}", n));
+        }
+        Some(n) => n,
+        None => max_degree,
+    };
+    let gen = perms
+        .iter()
+        .map(|cycles| {
+            let mut images: Vec<usize> = (0..n).collect();
+            let mut seen = vec![false; n];
+            for cycle in cycles {
+                for (i, &x) in cycle.iter().enumerate() {
+                    if seen[x] {
+                        return Err(format!("point {} appears twice in a permutation", x + 1));
+                    }
+                    seen[x] = true;
+                    images[x] = cycle[(i + 1) % cycle.len()];
+                }
+            }
+            Ok(Perm::from_vec_unchecked(images))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((n, gen))
+}
+
+/// Returns g in GAP syntax with 1-based points, such as (1,4)(3,10). The identity is ().
+pub fn to_gap(g: &Perm) -> String {
+    let n = g.size();
+    let mut visited = vec![false; n];
+    let mut result = String::new();
+    for i in 0..n {
+        if visited[i] || g[i] == i {
+            continue;
+        }
+        let mut cycle = vec![];
+        let mut j = i;
+        while !visited[j] {
+            visited[j] = true;
+            cycle.push((j + 1).to_string());
+            j = g[j];
+        }
+        result += &format!("({})", cycle.join(","));
+    }
+    if result.is_empty() {
+        result += "()";
+    }
+    result
+}
+
+/// Returns the group generated by gen in GAP syntax, such as Group([ (1,2,3), (1,2) ]).
+pub fn to_gap_group(gen: &[Perm]) -> String {
+    if gen.is_empty() {
+        return "Group(())".to_string();
+    }
+    let gen: Vec<String> = gen.iter().map(to_gap).collect();
+    format!("Group([ {} ])", gen.join(", "))
+}
+
+/// Parses a cycle such as (1,8,9) starting at pos. Returns the 0-based cycle and the position
+/// after it, or None if the parenthesis does not start a cycle.
+fn parse_cycle(text: &str, pos: usize) -> Result<Option<(Vec<usize>, usize)>, String> {
+    let Some(len) = text[pos + 1..].find(')') else {
+        return Ok(None);
+    };
+    let body = &text[pos + 1..pos + 1 + len];
+    if !body
+        .chars()
+        .all(|c| c.is_ascii_digit() || c == ',' || c.is_whitespace())
+    {
+        return Ok(None);
+    }
+    let mut cycle = vec![];
+    if !body.trim().is_empty() {
+        for x in body.split(',') {
+            let x = x.trim();
+            match x.parse::<usize>() {
+                Ok(x) if x >= 1 => cycle.push(x - 1),
+                _ => return Err(format!("invalid point {:?} in ({})", x, body)),
+            }
+        }
+    }
+    Ok(Some((cycle, pos + len + 2)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groups::mathieu12;
+
+    #[test]
+    fn parse_gap_test() {
+        let (n, gen) = mathieu12::generators();
+        let text = "Group((1,4)(3,10)(5,11)(6,12), (1,8,9)(2,3,4)(5,12,11)(6,10,7))";
+        assert_eq!(parse_gap(text, None), Ok((n, gen.clone())));
+        let text =
+            "# M12\nb11 := (1,4)(3,10)(5,11)(6,12);\nb21 := (1,8,9)(2,3,4)\\\n(5,12,11)(6,10,7);\n";
+        assert_eq!(parse_gap(text, None), Ok((n, gen.clone())));
+        assert_eq!(parse_gap(&to_gap_group(&gen), None), Ok((n, gen)));
+        assert_eq!(
+            parse_gap("[ (), (1,2) ]", Some(3)),
+            Ok((3, vec![Perm::e(3), Perm::new(vec![1, 0, 2])]))
+        );
+        assert!(parse_gap("(1,2)(2,3)", None).is_err());
+        assert!(parse_gap("(0,1)", None).is_err());
+        assert!(parse_gap("(1,3)", Some(2)).is_err());
+    }
+    #[test]
+    fn parse_gap_test_operators() {
+        assert!(parse_gap("(1,2)*(2,3)", None).is_err());
+        assert!(parse_gap("(1,2)^2", None).is_err());
+        assert!(parse_gap("(1,2)/(2,3)", None).is_err());
+        assert!(parse_gap("(1,2)^-1", None).is_err());
+        assert!(parse_gap("a * b", None).is_err());
+        assert!(parse_gap("((1,2))", None).is_err());
+        assert!(parse_gap("Group((1,2)))", None).is_err());
+        assert!(parse_gap("Group(a, (1,2))", None).is_err());
+        assert_eq!(
+            parse_gap("G_1 := Group( [ (1,2) ] );;", None),
+            Ok((2, vec![Perm::new(vec![1, 0])]))
+        );
+    }
+    #[test]
+    fn to_gap_test() {
+        assert_eq!(to_gap(&Perm::new(vec![1, 2, 0, 4, 3])), "(1,2,3)(4,5)");
+        assert_eq!(to_gap(&Perm::e(2)), "()");
+        assert_eq!(to_gap_group(&[]), "Group(())");
+    }
+}
@@ -0,0 +1,163 @@
+use crate::perm::{Perm, Point};
+use crate::schreier::{bsgs_order, incrementally_build_bsgs};
+use crate::transversal::orbit;
+use num_bigint::BigInt;
+use rand::rngs::ThreadRng;
+use rand::Rng;
+
+/// The number of steps of the product replacement algorithm done before the first output
+const WARM_UP_STEPS: usize = 50;
+
+/// Pseudo-random elements of G = \<gen\> by the product replacement algorithm
+/// with an accumulator ("rattle"), which needs no BSGS of G.
+///
+/// Reference: F. Celler, C. R. Leedham-Green, S. H. Murray, A. C. Niemeyer, E. A. O'Brien,
+/// Generating random elements of a finite group, Comm. Algebra 23 (1995), 4931-4948.
+pub struct ProductReplacement<P: Point = usize, R: Rng = ThreadRng> {
+    state: Vec<Perm<P>>,
+    accumulator: Perm<P>,
+    rnd: R,
+}
+
+impl ProductReplacement {
+    pub fn new(n: usize, gen: &[Perm]) -> Self {
+        Self::with_rng(n, gen, rand::thread_rng())
+    }
+}
+
+impl<P: Point, R: Rng> ProductReplacement<P, R> {
+    /// The same as new, with the given source of randomness
+    pub fn with_rng(n: usize, gen: &[Perm<P>], rnd: R) -> Self {
+        let mut state: Vec<Perm<P>> = gen.to_vec();
+        if state.is_empty() {
+            state.push(Perm::identity(n));
+        }
+        // The state needs some room for the products to mix.
+        let k = state.len();
+        for i in k..10.max(k + 1) {
+            state.push(state[i % k].clone());
+        }
+        let mut result = ProductReplacement {
+            state,
+            accumulator: Perm::identity(n),
+            rnd,
+        };
+        for _ in 0..WARM_UP_STEPS {
+            result.next();
+        }
+        result
+    }
+}
+
+impl<P: Point, R: Rng> Iterator for ProductReplacement<P, R> {
+    type Item = Perm<P>;
+
+    fn next(&mut self) -> Option<Perm<P>> {
+        let r = self.state.len();
+        let i = self.rnd.gen_range(0..r);
+        let j = (i + self.rnd.gen_range(1..r)) % r;
+        let y = if self.rnd.gen() {
+            self.state[j].clone()
+        } else {
+            self.state[j].inv()
+        };
+        self.state[i] = if self.rnd.gen() {
+            self.state[i].compose(&y)
+        } else {
+            y.compose(&self.state[i])
+        };
+        self.accumulator *= &self.state[i];
+        Some(self.accumulator.clone())
+    }
+}
+
+/// Checks if G = \<gen\> is S_n or A_n.
+///
+/// By Jordan's theorem, a primitive group of degree n containing a cycle of prime length p
+/// with p <= n - 3 contains A_n. A transitive group containing a p-cycle with p > n / 2
+/// is primitive. The test looks for random elements with a p-cycle, n / 2 < p < n - 2,
+/// whose suitable power is the p-cycle itself since the other cycles are shorter than p.
+/// A positive answer is always correct. For n >= 8, a negative answer is wrong with
+/// a negligible probability; for n < 8, where no such p exists, the order is computed.
+pub fn is_giant(n: usize, gen: &[Perm]) -> bool {
+    if n < 8 {
+        let mut rnd = rand::thread_rng();
+        let (beta_transversals, _) = incrementally_build_bsgs(n, &[], gen, &mut rnd);
+        return n >= 2 && bsgs_order(&beta_transversals) * 2 >= factorial(n);
+    }
+    if orbit(n, gen, 0).len() < n {
+        return false;
+    }
+    // The proportion of such elements in A_n or S_n is about log 2 / log n.
+    let tries = 40 * (usize::BITS - n.leading_zeros()) as usize;
+    ProductReplacement::new(n, gen).take(tries).any(|g| {
+        g.cycle_type()
+            .iter()
+            .any(|&len| 2 * len > n && len + 2 < n && is_prime(len))
+    })
+}
+
+/// Returns |G| if G = \<gen\> is recognized as S_n or A_n by is_giant, and None otherwise.
+pub fn giant_order(n: usize, gen: &[Perm]) -> Option<BigInt> {
+    if !is_giant(n, gen) {
+        return None;
+    }
+    // G = S_n if and only if some generator is odd.
+    let odd = gen.iter().any(|g| (n - g.cycle_type().len()) % 2 == 1);
+    let order = factorial(n);
+    Some(if odd { order } else { order / 2 })
+}
+
+fn factorial(n: usize) -> BigInt {
+    (1..=n).product()
+}
+
+fn is_prime(x: usize) -> bool {
+    x >= 2
+        && (2..)
+            .take_while(|p| p * p <= x)
+            .all(|p| !x.is_multiple_of(p))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groups::{mathieu12, rubik};
+
+    fn star(n: usize) -> Vec<Perm> {
+        (0..n - 1)
+            .map(|i| {
+                let mut p: Vec<_> = (0..n).collect();
+                p.swap(i, n - 1);
+                Perm::new(p)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn is_giant_test() {
+        assert!(is_giant(10, &star(10)));
+        assert!(is_giant(5, &star(5)));
+        // A_12 = <(0 1 2), (1 2 ... 11)>
+        let mut cycle: Vec<_> = (2..12).collect();
+        cycle.insert(0, 0);
+        cycle.push(1);
+        let a12 = vec![
+            Perm::new([vec![1, 2, 0], (3..12).collect()].concat()),
+            Perm::new(cycle),
+        ];
+        assert_eq!(giant_order(12, &a12), Some(factorial(12) / 2));
+        assert_eq!(giant_order(10, &star(10)), Some(factorial(10)));
+        // M12 has no elements of order 7.
+        let (n, gen) = mathieu12::generators();
+        assert!(!is_giant(n, &gen));
+        let (n, gen) = rubik::generators();
+        assert!(!is_giant(n, &gen));
+        // D_10
+        let d10 = vec![
+            Perm::new(vec![1, 2, 3, 4, 0]),
+            Perm::new(vec![0, 4, 3, 2, 1]),
+        ];
+        assert!(!is_giant(5, &d10));
+    }
+}
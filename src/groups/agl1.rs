@@ -0,0 +1,30 @@
+// Utility functions for the affine group AGL(1, q).
+use crate::groups::field::Field;
+use crate::perm::Perm;
+
+/// Returns AGL(1, q) = {x |-> ax + b | a != 0} acting on GF(q), where q is a prime power.
+/// For a prime p, the points 0, ..., p-1 are the residues modulo p.
+///
+/// AGL(1, q) is generated by x |-> x + 1 and x |-> ωx, where ω is a primitive element.
+pub fn generators(q: usize) -> (usize, Vec<Perm>) {
+    let f = Field::new(q);
+    let w = f.primitive();
+    let one = f.pow(w, 0);
+    let translation: Vec<usize> = (0..q).map(|x| f.add(x, one)).collect();
+    let multiplication: Vec<usize> = (0..q).map(|x| f.mul(w, x)).collect();
+    (q, vec![Perm::new(translation), Perm::new(multiplication)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schreier::order;
+
+    #[test]
+    fn agl1_order_test() {
+        for p in [2, 3, 5, 7, 11, 13, 8, 9] {
+            let (n, gen) = generators(p);
+            assert_eq!(order(n, &gen), (p * (p - 1)).into());
+        }
+    }
+}
@@ -0,0 +1,37 @@
+// Utility functions for the alternating group A_n.
+use crate::groups::util::get_cycle;
+use crate::perm::Perm;
+
+/// Returns A_n = <(0 1 2), (0 1 ... n-1)> for odd n, and <(0 1 2), (1 2 ... n-1)> for even n.
+pub fn generators(n: usize) -> (usize, Vec<Perm>) {
+    if n <= 2 {
+        return (n, vec![]);
+    }
+    let three_cycle = get_cycle(n, &[vec![0, 1, 2]]);
+    // The long cycle has odd length, so it is an even permutation.
+    let long_cycle: Vec<usize> = if n % 2 == 1 {
+        (0..n).collect()
+    } else {
+        (1..n).collect()
+    };
+    (n, vec![three_cycle, get_cycle(n, &[long_cycle])])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schreier::order;
+    use num_bigint::BigInt;
+
+    #[test]
+    fn alternating_order_test() {
+        for n in 1..10usize {
+            let (m, gen) = generators(n);
+            assert_eq!(m, n);
+            assert_eq!(
+                order(n, &gen),
+                (3..=n).map(BigInt::from).product::<BigInt>()
+            );
+        }
+    }
+}
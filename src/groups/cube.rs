@@ -0,0 +1,91 @@
+// Utility functions for the n x n x n cube puzzles.
+use crate::perm::Perm;
+use std::collections::HashMap;
+
+/// Returns the group of the n x n x n cube acting on its 6n^2 facelets,
+/// generated by the quarter turns of every layer except the central layers of odd cubes,
+/// so that the centre facelets of odd cubes stay fixed.
+///
+/// A facelet is given by the position of its cubie and its outward normal,
+/// with coordinates in {-(n-1), -(n-3), ..., n-1}. The facelets of the face with normal
+/// +x, -x, +y, -y, +z, -z are numbered consecutively in this order.
+/// generators(3) is the Rubik's Cube group up to the numbering of the facelets.
+pub fn generators(n: usize) -> (usize, Vec<Perm>) {
+    let m = n as i32 - 1;
+    let coords: Vec<i32> = (0..n as i32).map(|i| 2 * i - m).collect();
+    let mut facelets = vec![];
+    for axis in 0..3 {
+        for sign in [1, -1] {
+            for &u in &coords {
+                for &v in &coords {
+                    let mut pos = [0; 3];
+                    pos[axis] = sign * m;
+                    pos[(axis + 1) % 3] = u;
+                    pos[(axis + 2) % 3] = v;
+                    let mut normal = [0; 3];
+                    normal[axis] = sign;
+                    facelets.push((pos, normal));
+                }
+            }
+        }
+    }
+    let index: HashMap<_, _> = facelets.iter().enumerate().map(|(i, &f)| (f, i)).collect();
+    let mut gen = vec![];
+    for axis in 0..3 {
+        for &layer in &coords {
+            if layer == 0 {
+                continue;
+            }
+            let image = facelets
+                .iter()
+                .map(|&(pos, normal)| {
+                    if pos[axis] == layer {
+                        index[&(rotate(pos, axis), rotate(normal, axis))]
+                    } else {
+                        index[&(pos, normal)]
+                    }
+                })
+                .collect();
+            gen.push(Perm::new(image));
+        }
+    }
+    (facelets.len(), gen)
+}
+
+/// Rotates v by a quarter turn about the given axis.
+fn rotate(v: [i32; 3], axis: usize) -> [i32; 3] {
+    let [x, y, z] = v;
+    match axis {
+        0 => [x, -z, y],
+        1 => [z, y, -x],
+        _ => [-y, x, z],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groups::rubik;
+    use crate::schreier::order;
+    use num_bigint::BigInt;
+
+    fn factorial(n: u32) -> BigInt {
+        (1..=n).map(BigInt::from).product()
+    }
+
+    #[test]
+    fn cube_order_test() {
+        // The 2x2x2 cube, with the whole cube rotations: 8! 3^7
+        let (n, gen) = generators(2);
+        assert_eq!(order(n, &gen), 88179840.into());
+        let (n, gen) = generators(3);
+        assert_eq!(order(n, &gen), order(48, &rubik::generators().1));
+    }
+    #[test]
+    fn cube_order_test_4x4x4() {
+        // 8! 3^7 for the corners, 24! for the edges and 24! / 2 for the centres
+        let (n, gen) = generators(4);
+        let expected = factorial(8) * BigInt::from(3).pow(7) * factorial(24) * factorial(24) / 2;
+        assert_eq!(order(n, &gen), expected);
+    }
+}
@@ -0,0 +1,24 @@
+// Utility functions for the cyclic group C_n.
+use crate::perm::Perm;
+
+/// Returns C_n = <(0 1 ... n-1)>.
+pub fn generators(n: usize) -> (usize, Vec<Perm>) {
+    let cycle: Vec<usize> = (0..n).map(|i| (i + 1) % n).collect();
+    (n, vec![Perm::new(cycle)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schreier::order;
+    use num_bigint::BigInt;
+
+    #[test]
+    fn cyclic_order_test() {
+        for n in 1..10usize {
+            let (m, gen) = generators(n);
+            assert_eq!(m, n);
+            assert_eq!(order(n, &gen), BigInt::from(n));
+        }
+    }
+}
@@ -0,0 +1,26 @@
+// Utility functions for the dihedral group of order 2n.
+use crate::perm::Perm;
+
+/// Returns the symmetry group of the regular n-gon with vertices 0, 1, ..., n-1,
+/// generated by the rotation i |-> i + 1 and the reflection i |-> -i.
+pub fn generators(n: usize) -> (usize, Vec<Perm>) {
+    let rotation: Vec<usize> = (0..n).map(|i| (i + 1) % n).collect();
+    let reflection: Vec<usize> = (0..n).map(|i| (n - i) % n).collect();
+    (n, vec![Perm::new(rotation), Perm::new(reflection)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schreier::order;
+    use num_bigint::BigInt;
+
+    #[test]
+    fn dihedral_order_test() {
+        for n in 1..10usize {
+            let (m, gen) = generators(n);
+            assert_eq!(m, n);
+            assert_eq!(order(n, &gen), BigInt::from(if n <= 2 { n } else { 2 * n }));
+        }
+    }
+}
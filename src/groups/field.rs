@@ -0,0 +1,166 @@
+// Arithmetic in finite fields GF(q) for the projective and affine groups.
+use crate::perm::Perm;
+
+/// GF(q) with q = p^k. An element is encoded as the integer whose base-p digits are
+/// the coefficients of a polynomial modulo an irreducible polynomial of degree k.
+pub struct Field {
+    pub q: usize,
+    p: usize,
+    /// exp\[i\] = ω^i for a primitive element ω
+    exp: Vec<usize>,
+    /// log\[x\] = i with ω^i = x, for x != 0
+    log: Vec<usize>,
+}
+
+impl Field {
+    /// Panics if q is not a prime power.
+    pub fn new(q: usize) -> Self {
+        assert!(q >= 2);
+        let p = (2..=q).find(|&p| q.is_multiple_of(p)).unwrap();
+        let mut k = 0;
+        let mut rest = q;
+        while rest.is_multiple_of(p) {
+            rest /= p;
+            k += 1;
+        }
+        assert_eq!(rest, 1, "{} is not a prime power", q);
+        // Monic polynomials of degree k are encoded as q + (the lower coefficients).
+        let modulus = (q..2 * q).find(|&f| is_irreducible(f, p, k)).unwrap();
+        let mul = |a: usize, b: usize| poly_mul_mod(a, b, modulus, p);
+        // Finds a primitive element.
+        for w in 2.min(q - 1)..q {
+            let mut exp = vec![1];
+            let mut x = w;
+            while x != 1 {
+                exp.push(x);
+                x = mul(x, w);
+            }
+            if exp.len() == q - 1 {
+                let mut log = vec![0; q];
+                for (i, &x) in exp.iter().enumerate() {
+                    log[x] = i;
+                }
+                return Field { q, p, exp, log };
+            }
+        }
+        unreachable!()
+    }
+
+    /// Returns a primitive element ω, a generator of the multiplicative group.
+    pub fn primitive(&self) -> usize {
+        self.exp[1 % self.exp.len()]
+    }
+
+    pub fn add(&self, mut a: usize, mut b: usize) -> usize {
+        let (mut result, mut digit) = (0, 1);
+        while a > 0 || b > 0 {
+            result += (a % self.p + b % self.p) % self.p * digit;
+            a /= self.p;
+            b /= self.p;
+            digit *= self.p;
+        }
+        result
+    }
+
+    pub fn neg(&self, mut a: usize) -> usize {
+        let (mut result, mut digit) = (0, 1);
+        while a > 0 {
+            result += (self.p - a % self.p) % self.p * digit;
+            a /= self.p;
+            digit *= self.p;
+        }
+        result
+    }
+
+    pub fn mul(&self, a: usize, b: usize) -> usize {
+        if a == 0 || b == 0 {
+            return 0;
+        }
+        self.exp[(self.log[a] + self.log[b]) % (self.q - 1)]
+    }
+
+    /// Returns a^{-1}. a must be nonzero.
+    pub fn inv(&self, a: usize) -> usize {
+        assert_ne!(a, 0);
+        self.exp[(self.q - 1 - self.log[a]) % (self.q - 1)]
+    }
+
+    /// Returns a^e.
+    pub fn pow(&self, a: usize, e: usize) -> usize {
+        if a == 0 {
+            return (e == 0) as usize;
+        }
+        self.exp[self.log[a] * e % (self.q - 1)]
+    }
+
+    /// Returns the action of x |-> (ax + b) / (cx + d) on the projective line,
+    /// where the points 0, ..., q-1 are the field elements and q is ∞.
+    pub fn mobius(&self, a: usize, b: usize, c: usize, d: usize) -> Perm {
+        let q = self.q;
+        let apply = |num: usize, den: usize| {
+            if den == 0 {
+                q
+            } else {
+                self.mul(num, self.inv(den))
+            }
+        };
+        let mut image: Vec<usize> = (0..q)
+            .map(|x| apply(self.add(self.mul(a, x), b), self.add(self.mul(c, x), d)))
+            .collect();
+        // ∞ |-> a / c
+        image.push(apply(a, c));
+        Perm::new(image)
+    }
+
+    /// Checks if a is a nonzero square.
+    pub fn is_square(&self, a: usize) -> bool {
+        a != 0 && (self.p == 2 || self.log[a].is_multiple_of(2))
+    }
+}
+
+/// Returns the coefficients of an encoded polynomial, the constant term first.
+fn digits(mut a: usize, p: usize) -> Vec<usize> {
+    let mut result = vec![];
+    while a > 0 {
+        result.push(a % p);
+        a /= p;
+    }
+    result
+}
+
+/// Returns the remainder of the polynomial a modulo the monic polynomial f.
+fn poly_mod(a: &[usize], f: &[usize], p: usize) -> Vec<usize> {
+    let mut a = a.to_vec();
+    let k = f.len() - 1;
+    while a.len() > k {
+        let c = a.pop().unwrap();
+        let shift = a.len() - k;
+        for i in 0..k {
+            a[shift + i] = (a[shift + i] + (p - c) * f[i]) % p;
+        }
+    }
+    a
+}
+
+fn poly_mul_mod(a: usize, b: usize, f: usize, p: usize) -> usize {
+    let (a, b, f) = (digits(a, p), digits(b, p), digits(f, p));
+    let mut c = vec![0; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            c[i + j] = (c[i + j] + x * y) % p;
+        }
+    }
+    poly_mod(&c, &f, p)
+        .iter()
+        .rev()
+        .fold(0, |acc, &x| acc * p + x)
+}
+
+/// Checks if the monic polynomial f of degree k has no monic factor of degree 1..=k/2.
+fn is_irreducible(f: usize, p: usize, k: usize) -> bool {
+    let f = digits(f, p);
+    (1..=k / 2).all(|d| {
+        let q = p.pow(d as u32);
+        (q..2 * q).all(|g| poly_mod(&f, &digits(g, p), p).iter().any(|&x| x != 0))
+    })
+}
@@ -0,0 +1,23 @@
+// Utility functions for the Mathieu group M11.
+use crate::groups::mathieu12;
+use crate::groups::util::stabilizer_of_last_points;
+use crate::perm::Perm;
+
+/// Returns M11 as the stabilizer of the point 11 in M12.
+pub fn generators() -> (usize, Vec<Perm>) {
+    let (n, gen) = mathieu12::generators();
+    stabilizer_of_last_points(n, &gen, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schreier::order;
+
+    #[test]
+    fn mathieu11_order_test() {
+        let (n, gen) = generators();
+        assert_eq!(n, 11);
+        assert_eq!(order(n, &gen), 7920.into());
+    }
+}
@@ -0,0 +1,23 @@
+// Utility functions for the Mathieu group M22.
+use crate::groups::mathieu24;
+use crate::groups::util::stabilizer_of_last_points;
+use crate::perm::Perm;
+
+/// Returns M22 as the stabilizer of the points 22 and 23 in M24.
+pub fn generators() -> (usize, Vec<Perm>) {
+    let (n, gen) = mathieu24::generators();
+    stabilizer_of_last_points(n, &gen, 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schreier::order;
+
+    #[test]
+    fn mathieu22_order_test() {
+        let (n, gen) = generators();
+        assert_eq!(n, 22);
+        assert_eq!(order(n, &gen), 443520.into());
+    }
+}
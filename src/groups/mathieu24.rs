@@ -0,0 +1,38 @@
+// Utility functions for the Mathieu group M24.
+use crate::groups::field::Field;
+use crate::groups::psl2;
+use crate::perm::Perm;
+
+/// Returns M24 acting on the projective line over GF(23), numbered as in psl2::generators.
+///
+/// M24 is generated by PSL(2, 23) and δ, which fixes 0 and ∞ and maps x to x^3 / 9
+/// if x is a square, and to 9x^3 otherwise (Conway and Sloane, SPLAG, Chapter 10).
+pub fn generators() -> (usize, Vec<Perm>) {
+    let (n, mut gen) = psl2::generators(23);
+    let f = Field::new(23);
+    let mut delta: Vec<usize> = (0..23)
+        .map(|x| {
+            let cube = f.pow(x, 3);
+            if f.is_square(x) {
+                f.mul(cube, f.inv(9))
+            } else {
+                f.mul(cube, 9)
+            }
+        })
+        .collect();
+    delta.push(23);
+    gen.push(Perm::new(delta));
+    (n, gen)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schreier::order;
+
+    #[test]
+    fn mathieu24_order_test() {
+        let (n, gen) = generators();
+        assert_eq!(order(n, &gen), 244823040.into());
+    }
+}
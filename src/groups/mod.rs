@@ -1,3 +1,17 @@
+pub mod agl1;
+pub mod alternating;
+pub mod cube;
+pub mod cyclic;
+pub mod dihedral;
+mod field;
+pub mod mathieu11;
 pub mod mathieu12;
+pub mod mathieu22;
+pub mod mathieu23;
+pub mod mathieu24;
+pub mod pgl2;
+pub mod psl2;
+pub mod pyraminx;
 pub mod rubik;
+pub mod symmetric;
 mod util;
@@ -0,0 +1,35 @@
+// Utility functions for the projective general linear group PGL(2, q).
+use crate::groups::field::Field;
+use crate::perm::Perm;
+
+/// Returns PGL(2, q) acting on the q+1 points of the projective line, where q is a prime power.
+/// The points are numbered as in psl2::generators.
+///
+/// PGL(2, q) is generated by x |-> x + 1, x |-> ωx and x |-> 1/x,
+/// where ω is a primitive element.
+pub fn generators(q: usize) -> (usize, Vec<Perm>) {
+    let f = Field::new(q);
+    let w = f.primitive();
+    let one = f.pow(w, 0);
+    let gen = vec![
+        f.mobius(one, one, 0, one),
+        f.mobius(w, 0, 0, one),
+        f.mobius(0, one, one, 0),
+    ];
+    (q + 1, gen)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schreier::order;
+
+    #[test]
+    fn pgl2_order_test() {
+        // |PGL(2, q)| = q(q^2 - 1)
+        for q in [2, 3, 4, 5, 7, 8, 9, 11, 16, 25, 27] {
+            let (n, gen) = generators(q);
+            assert_eq!(order(n, &gen), (q * (q * q - 1)).into());
+        }
+    }
+}
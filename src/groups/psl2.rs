@@ -0,0 +1,36 @@
+// Utility functions for the projective special linear group PSL(2, q).
+use crate::groups::field::Field;
+use crate::perm::Perm;
+
+/// Returns PSL(2, q) acting on the q+1 points of the projective line, where q is a prime power.
+/// The point q is ∞, and the others are the elements of GF(q) (see Field).
+///
+/// PSL(2, q) is generated by x |-> x + 1, x |-> ω^2 x and x |-> -1/x,
+/// where ω is a primitive element.
+pub fn generators(q: usize) -> (usize, Vec<Perm>) {
+    let f = Field::new(q);
+    let w = f.primitive();
+    let one = f.pow(w, 0);
+    let gen = vec![
+        f.mobius(one, one, 0, one),
+        f.mobius(f.mul(w, w), 0, 0, one),
+        f.mobius(0, f.neg(one), one, 0),
+    ];
+    (q + 1, gen)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schreier::order;
+
+    #[test]
+    fn psl2_order_test() {
+        // |PSL(2, q)| = q(q^2 - 1) / gcd(2, q - 1)
+        for q in [2, 3, 4, 5, 7, 8, 9, 11, 13, 16, 25, 27, 32, 49] {
+            let (n, gen) = generators(q);
+            let d = if q % 2 == 0 { 1 } else { 2 };
+            assert_eq!(order(n, &gen), (q * (q * q - 1) / d).into());
+        }
+    }
+}
@@ -0,0 +1,101 @@
+// Utility functions for the Pyraminx.
+use crate::perm::Perm;
+
+/// Returns the Pyraminx group acting on its 36 facelets,
+/// generated by the turns of the 4 tips and of the 4 two-layer corners.
+///
+/// The puzzle is the regular tetrahedron with vertices (1, 1, 1), (1, -1, -1), (-1, 1, -1)
+/// and (-1, -1, 1). Each face is cut into 9 triangular facelets, and the facelets of the face
+/// opposite to vertex i are numbered 9i, ..., 9i + 8.
+pub fn generators() -> (usize, Vec<Perm>) {
+    let vertices = [[1., 1., 1.], [1., -1., -1.], [-1., 1., -1.], [-1., -1., 1.]];
+    // A facelet is identified by its centroid.
+    let mut facelets = vec![];
+    for i in 0..4 {
+        let corners: Vec<[f64; 3]> = (0..4).filter(|&j| j != i).map(|j| vertices[j]).collect();
+        let point = |a: usize, b: usize, c: usize| -> [f64; 3] {
+            let mut p = [0.; 3];
+            for (k, x) in p.iter_mut().enumerate() {
+                *x = (a as f64 * corners[0][k]
+                    + b as f64 * corners[1][k]
+                    + c as f64 * corners[2][k])
+                    / 3.;
+            }
+            p
+        };
+        let centroid = |t: [[f64; 3]; 3]| -> [f64; 3] {
+            [0, 1, 2].map(|k| (t[0][k] + t[1][k] + t[2][k]) / 3.)
+        };
+        for a in 0..3 {
+            for b in 0..3 - a {
+                let c = 2 - a - b;
+                facelets.push(centroid([
+                    point(a + 1, b, c),
+                    point(a, b + 1, c),
+                    point(a, b, c + 1),
+                ]));
+                if c > 0 {
+                    facelets.push(centroid([
+                        point(a, b + 1, c),
+                        point(a + 1, b, c),
+                        point(a + 1, b + 1, c - 1),
+                    ]));
+                }
+            }
+        }
+    }
+    // The distance from the centre to a vertex is √3 and to a face is 1/√3.
+    // The cuts are at a third and two thirds of the height 4/√3 from each vertex.
+    let sqrt3 = 3f64.sqrt();
+    let cuts = [5. / 9. * sqrt3, sqrt3 / 9.];
+    let mut gen = vec![];
+    for v in &vertices {
+        let axis = v.map(|x| x / sqrt3);
+        for &cut in &cuts {
+            let image = (0..facelets.len())
+                .map(|i| {
+                    let p = facelets[i];
+                    if dot(&p, &axis) < cut {
+                        return i;
+                    }
+                    let r = rotate(&p, &axis);
+                    (0..facelets.len())
+                        .find(|&j| (0..3).all(|k| (facelets[j][k] - r[k]).abs() < 1e-9))
+                        .unwrap()
+                })
+                .collect();
+            gen.push(Perm::new(image));
+        }
+    }
+    (facelets.len(), gen)
+}
+
+fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    (0..3).map(|k| a[k] * b[k]).sum()
+}
+
+/// Rotates p by a third of a turn about the unit vector axis (Rodrigues' formula).
+fn rotate(p: &[f64; 3], axis: &[f64; 3]) -> [f64; 3] {
+    let (sin, cos) = (2. * std::f64::consts::PI / 3.).sin_cos();
+    let cross = [
+        axis[1] * p[2] - axis[2] * p[1],
+        axis[2] * p[0] - axis[0] * p[2],
+        axis[0] * p[1] - axis[1] * p[0],
+    ];
+    let d = dot(axis, p);
+    [0, 1, 2].map(|k| p[k] * cos + cross[k] * sin + axis[k] * d * (1. - cos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schreier::order;
+
+    #[test]
+    fn pyraminx_order_test() {
+        // 3^4 for the tips, 3^4 for the axial pieces, 6! / 2 * 2^5 for the edges
+        let (n, gen) = generators();
+        assert_eq!(n, 36);
+        assert_eq!(order(n, &gen), 75582720.into());
+    }
+}
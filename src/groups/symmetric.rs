@@ -0,0 +1,33 @@
+// Utility functions for the symmetric group S_n.
+use crate::perm::Perm;
+
+/// Returns S_n = <(0 1 ... n-1), (0 1)>.
+pub fn generators(n: usize) -> (usize, Vec<Perm>) {
+    if n <= 1 {
+        return (n, vec![]);
+    }
+    let mut cycle: Vec<_> = (1..n).collect();
+    cycle.push(0);
+    let mut transposition: Vec<_> = (0..n).collect();
+    transposition.swap(0, 1);
+    (n, vec![Perm::new(cycle), Perm::new(transposition)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schreier::order;
+    use num_bigint::BigInt;
+
+    #[test]
+    fn symmetric_order_test() {
+        for n in 1..10usize {
+            let (m, gen) = generators(n);
+            assert_eq!(m, n);
+            assert_eq!(
+                order(n, &gen),
+                (1..=n).map(BigInt::from).product::<BigInt>()
+            );
+        }
+    }
+}
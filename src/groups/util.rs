@@ -1,4 +1,5 @@
 use crate::perm::Perm;
+use crate::schreier::incrementally_build_bsgs;
 
 /// Given a list of cycles, this function returns its composition.
 pub fn get_cycle(n: usize, a: &[Vec<usize>]) -> Perm {
@@ -12,3 +13,17 @@ pub fn get_cycle(n: usize, a: &[Vec<usize>]) -> Perm {
     }
     e
 }
+
+/// Returns generators of the pointwise stabilizer of the last k points in G = <gen>,
+/// restricted to the first n - k points.
+pub fn stabilizer_of_last_points(n: usize, gen: &[Perm], k: usize) -> (usize, Vec<Perm>) {
+    let mut rnd = rand::thread_rng();
+    let base: Vec<usize> = (n - k..n).rev().collect();
+    let (_, s) = incrementally_build_bsgs(n, &base, gen, &mut rnd);
+    let gen = s
+        .iter()
+        .filter(|g| (n - k..n).all(|x| g[x] == x))
+        .map(|g| Perm::new((0..n - k).map(|x| g[x]).collect()))
+        .collect();
+    (n - k, gen)
+}
@@ -1,5 +1,6 @@
 use crate::perm::Perm;
-use crate::schreier::incrementally_build_bsgs;
+use crate::schreier::{incrementally_build_bsgs, strip};
+use crate::transversal::Transversal;
 
 /// Checks if a given mapping (x\[i\] |-> y\[i\]) extends to a group homomorphism
 /// &lt;x&gt; -> &lt;y&gt;.
@@ -14,12 +15,165 @@ pub fn is_homomorphism(n: usize, m: usize, x: &[Perm], y: &[Perm]) -> bool {
     }
     let (beta_transversals, _) = incrementally_build_bsgs(n, &[], x, &mut rnd);
     let beta: Vec<_> = beta_transversals.iter().map(|&(b, _)| b).collect();
-    eprintln!("beta = {:?}", beta);
     // Is beta also a BSGS of <xy>?
     let (beta_transversals, _) = incrementally_build_bsgs(n + m, &beta, &xy, &mut rnd);
     beta_transversals.len() == beta.len()
 }
 
+/// A homomorphism from G = \<gen\> of degree n to a permutation group of degree m,
+/// determined by the images of the generators.
+///
+/// The homomorphism is represented by its graph {g.concat(φ(g))}, a permutation group of
+/// degree n + m isomorphic to G, with two stabilizer chains: one whose base consists of
+/// points of G, and one whose base starts with points of the image.
+pub struct Homomorphism {
+    n: usize,
+    m: usize,
+    gen: Vec<Perm>,
+    images: Vec<Perm>,
+    /// A BSGS of the graph whose base points are in 0..n
+    domain_first: Vec<(usize, Transversal)>,
+    /// A BSGS of the graph whose first image_levels base points are in n..n + m
+    image_first: Vec<(usize, Transversal)>,
+    image_levels: usize,
+    /// The strong generators of image_first
+    image_first_s: Vec<Perm>,
+}
+
+impl Homomorphism {
+    /// Returns the homomorphism gen\[i\] |-> images\[i\],
+    /// or None if the mapping does not extend to a homomorphism.
+    pub fn new(n: usize, m: usize, gen: &[Perm], images: &[Perm]) -> Option<Self> {
+        assert_eq!(gen.len(), images.len());
+        let mut rnd = rand::thread_rng();
+        let graph: Vec<Perm> = gen.iter().zip(images).map(|(x, y)| x.concat(y)).collect();
+        let (g_transversals, _) = incrementally_build_bsgs(n, &[], gen, &mut rnd);
+        let g_base: Vec<usize> = g_transversals.iter().map(|&(beta, _)| beta).collect();
+        // The graph is a homomorphism if and only if only the identity of G is mapped to
+        // a nontrivial element, that is, a base of G is also a base of the graph.
+        let (domain_first, _) = incrementally_build_bsgs(n + m, &g_base, &graph, &mut rnd);
+        if domain_first.len() != g_base.len() {
+            return None;
+        }
+        let (h_transversals, _) = incrementally_build_bsgs(m, &[], images, &mut rnd);
+        let h_base: Vec<usize> = h_transversals.iter().map(|&(beta, _)| n + beta).collect();
+        let (image_first, image_first_s) =
+            incrementally_build_bsgs(n + m, &h_base, &graph, &mut rnd);
+        Some(Homomorphism {
+            n,
+            m,
+            gen: gen.to_vec(),
+            images: images.to_vec(),
+            domain_first,
+            image_first,
+            image_levels: h_base.len(),
+            image_first_s,
+        })
+    }
+
+    /// Returns the action of G = \<gen\> on an orbit.
+    /// The points of the image are the indices of orbit.
+    pub fn action_on_orbit(n: usize, gen: &[Perm], orbit: &[usize]) -> Self {
+        let mut index = vec![usize::MAX; n];
+        for (i, &a) in orbit.iter().enumerate() {
+            index[a] = i;
+        }
+        let images: Vec<Perm> = gen
+            .iter()
+            .map(|g| Perm::new(orbit.iter().map(|&a| index[g[a]]).collect()))
+            .collect();
+        Self::new(n, orbit.len(), gen, &images).unwrap()
+    }
+
+    /// Returns the action of G = \<gen\> on a G-invariant partition given as labels,
+    /// as returned by minimal_block. The parts are numbered in ascending order of their labels.
+    pub fn action_on_blocks(n: usize, gen: &[Perm], labels: &[usize]) -> Self {
+        let mut index = vec![usize::MAX; n];
+        let mut m = 0;
+        for a in 0..n {
+            if labels[a] == a {
+                index[a] = m;
+                m += 1;
+            }
+        }
+        let mut images = vec![];
+        for g in gen {
+            let mut image = vec![0; m];
+            for a in 0..n {
+                image[index[labels[a]]] = index[labels[g[a]]];
+            }
+            images.push(Perm::new(image));
+        }
+        Self::new(n, m, gen, &images).unwrap()
+    }
+
+    /// Returns φ(g), or None if g is not in G.
+    pub fn apply(&self, g: &Perm) -> Option<Perm> {
+        // g.concat(e) = rest * (g, φ(g)), so rest = (e, φ(g)^{-1}) if g is in G.
+        let (_, rest) = strip(&g.concat(&Perm::e(self.m)), &self.domain_first);
+        let (rest_g, rest_h) = rest.split(self.n);
+        if rest_g != Perm::e(self.n) {
+            return None;
+        }
+        Some(rest_h.inv())
+    }
+
+    /// Returns the degree of G.
+    pub fn domain_degree(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the generators of G.
+    pub fn domain(&self) -> &[Perm] {
+        &self.gen
+    }
+
+    /// Returns the generators of the image φ(G).
+    pub fn image(&self) -> &[Perm] {
+        &self.images
+    }
+
+    /// Returns the degree of the image.
+    pub fn image_degree(&self) -> usize {
+        self.m
+    }
+
+    /// Returns a generator set of the kernel of φ.
+    pub fn kernel(&self) -> Vec<Perm> {
+        // Strong generators fixing the base of the image generate the stabilizer of
+        // all points of the image in the graph, which is {(k, e)} for k in the kernel.
+        let image_base = &self.image_first[..self.image_levels];
+        self.image_first_s
+            .iter()
+            .filter(|s| image_base.iter().all(|&(beta, _)| s[beta] == beta))
+            .map(|s| s.split(self.n).0)
+            .filter(|k| *k != Perm::e(self.n))
+            .collect()
+    }
+
+    /// Returns some g with φ(g) = h, or None if h is not in the image.
+    pub fn preimage(&self, h: &Perm) -> Option<Perm> {
+        // e.concat(h) = rest * (g, φ(g)), so rest = (g^{-1}, e) if h is in the image.
+        let image_first = &self.image_first[..self.image_levels];
+        let (_, rest) = strip(&Perm::e(self.n).concat(h), image_first);
+        let (rest_g, rest_h) = rest.split(self.n);
+        if rest_h != Perm::e(self.m) {
+            return None;
+        }
+        Some(rest_g.inv())
+    }
+
+    /// Returns a generator set of φ^{-1}(H), where H = \<h\> is a subgroup of the image,
+    /// or None if some element of h is not in the image.
+    pub fn subgroup_preimage(&self, h: &[Perm]) -> Option<Vec<Perm>> {
+        let mut result = self.kernel();
+        for h in h {
+            result.push(self.preimage(h)?);
+        }
+        Some(result)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,4 +257,40 @@ mod tests {
             .collect();
         is_homomorphism(n, n / 2, &gen, &oddgen);
     }
+    #[test]
+    fn homomorphism_test_s4_s3() {
+        use crate::blocks::minimal_block;
+        use crate::schreier::order;
+        // S_4 acts on the partitions {{0, 1}, {2, 3}}, {{0, 2}, {1, 3}}, {{0, 3}, {1, 2}}
+        // as S_3, with the kernel V_4.
+        let n = 4;
+        let gen = vec![Perm::new(vec![1, 2, 3, 0]), Perm::new(vec![1, 0, 2, 3])];
+        let phi = Homomorphism::new(
+            n,
+            3,
+            &gen,
+            &[Perm::new(vec![1, 2, 0]), Perm::new(vec![0, 2, 1])],
+        );
+        assert!(phi.is_none());
+        let phi = Homomorphism::new(
+            n,
+            3,
+            &gen,
+            &[Perm::new(vec![2, 1, 0]), Perm::new(vec![0, 2, 1])],
+        )
+        .unwrap();
+        assert_eq!(order(n, &phi.kernel()), 4.into());
+        let h = Perm::new(vec![1, 2, 0]);
+        let g = phi.preimage(&h).unwrap();
+        assert_eq!(phi.apply(&g), Some(h));
+        // The preimage of <(0 2)> is D_8.
+        let d8 = phi.subgroup_preimage(&[Perm::new(vec![2, 1, 0])]).unwrap();
+        assert_eq!(order(n, &d8), 8.into());
+        // D_8 acts on the blocks {{0, 2}, {1, 3}} as S_2.
+        let labels = minimal_block(n, &d8, 0, 2);
+        let psi = Homomorphism::action_on_blocks(n, &d8, &labels);
+        assert_eq!(order(2, psi.image()), 2.into());
+        assert_eq!(order(n, &psi.kernel()), 4.into());
+        assert_eq!(psi.apply(&Perm::new(vec![0, 1, 3, 2])), None);
+    }
 }
@@ -0,0 +1,16 @@
+use num_bigint::BigInt;
+
+pub(crate) fn gcd(a: &BigInt, b: &BigInt) -> BigInt {
+    let zero = BigInt::from(0);
+    let (mut a, mut b) = (a.clone(), b.clone());
+    while b != zero {
+        let r = &a % &b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+pub(crate) fn lcm(a: &BigInt, b: &BigInt) -> BigInt {
+    a * b / gcd(a, b)
+}
@@ -1,5 +1,26 @@
+pub mod backtrack;
+pub mod blocks;
+pub mod bsgs;
+pub mod canonical;
+pub mod centralizer;
+pub mod composition;
+pub mod conjugacy;
+pub mod coset;
+pub mod elements;
+pub mod factorization;
+pub mod format;
+pub mod fpgroup;
+pub mod gap;
+pub mod giant;
 pub mod groups;
 pub mod homomorphism;
+mod integer;
+pub mod meataxe;
+pub mod normalizer;
 pub mod perm;
+pub mod presentation;
+pub mod product;
 pub mod schreier;
+pub mod subgroup;
+pub mod sylow;
 pub mod transversal;
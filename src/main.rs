@@ -1,67 +1,373 @@
+use num_bigint::BigInt;
+use rust_schreier::conjugacy::conjugacy_classes;
+use rust_schreier::factorization::factorization;
+use rust_schreier::format::{parse_generators, parse_perm};
+use rust_schreier::gap::parse_gap;
+use rust_schreier::homomorphism::is_homomorphism;
+use rust_schreier::meataxe::parse_meataxe;
 use rust_schreier::perm::Perm;
-use rust_schreier::schreier::order;
-
-fn get_cycle(n: usize, a: &[Vec<usize>]) -> Perm {
-    let mut e = Perm::e(n);
-    for a in a {
-        let mut t: Vec<_> = (0..n).collect();
-        for i in 0..a.len() {
-            t[a[i]] = a[(i + 1) % a.len()];
+use rust_schreier::schreier::{
+    bsgs_order, incrementally_build_bsgs, order, reduce_strong_generators, strip,
+};
+use rust_schreier::transversal::orbit;
+use std::io::Read;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: rust-schreier <COMMAND> [OPTIONS] [ARGS]
+
+Generators are read from FILE, or from stdin if FILE is omitted or '-'.
+In the plain format, a generator is given per line, and a permutation of the points
+0, 1, ..., n-1 is written in cycle notation, e.g. (0 1 2)(3 4), or as the list of images,
+e.g. [1, 2, 0, 4, 3]. Lines starting with '#' are ignored.
+Results are printed as JSON with 0-based points. Group orders are printed as strings.
+
+Commands:
+  order [FILE]                       the order of the group
+  orbits [FILE]                      the orbits of the group
+  contains ELEMENT [FILE]            whether ELEMENT is in the group
+  factor ELEMENT [FILE]              ELEMENT as a word in the generators,
+                                     where i+1 and -(i+1) stand for the i-th generator and its inverse
+  is-homomorphism FILE IMAGE_FILE    whether the i-th generator |-> the i-th image
+                                     extends to a homomorphism
+  bsgs [FILE]                        a base and strong generating set
+  classes [FILE]                     the conjugacy classes
+
+Options:
+  -d, --degree N       the degree of the group, if larger than the largest point moved
+  -f, --format FORMAT  the input format: plain (default), gap (1-based points, e.g.
+                       Group((1,2,3),(1,2))) or meataxe (ATLAS files, which may be concatenated).
+                       ELEMENT is written in GAP syntax for gap, and in the plain format otherwise
+  -h, --help           print this message
+";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(output) => {
+            println!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<String, String> {
+    let mut input = Input {
+        degree: None,
+        format: Format::Plain,
+    };
+    let mut positional = vec![];
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(USAGE.trim_end().to_string()),
+            "-d" | "--degree" => {
+                let value = iter.next().ok_or("missing value for --degree")?;
+                input.degree = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid degree {:?}", value))?,
+                );
+            }
+            "-f" | "--format" => {
+                let value = iter.next().ok_or("missing value for --format")?;
+                input.format = match value.as_str() {
+                    "plain" => Format::Plain,
+                    "gap" => Format::Gap,
+                    "meataxe" => Format::MeatAxe,
+                    _ => return Err(format!("unknown format {:?}", value)),
+                };
+            }
+            _ => positional.push(arg.as_str()),
+        }
+    }
+    let Some((&command, rest)) = positional.split_first() else {
+        return Err("missing command (see --help)".to_string());
+    };
+    let mut rnd = rand::thread_rng();
+    match (command, rest) {
+        ("order", [] | [_]) => {
+            let (n, gen) = input.read(rest.first().copied())?;
+            Ok(format!("{{\"order\": {}}}", json_bigint(&order(n, &gen))))
         }
-        e = e.compose(&Perm::new(t));
+        ("orbits", [] | [_]) => {
+            let (n, gen) = input.read(rest.first().copied())?;
+            let mut seen = vec![false; n];
+            let mut orbits = vec![];
+            for v in 0..n {
+                if seen[v] {
+                    continue;
+                }
+                let mut o = orbit(n, &gen, v);
+                o.sort_unstable();
+                for &x in &o {
+                    seen[x] = true;
+                }
+                orbits.push(json_list(&o));
+            }
+            Ok(format!("{{\"orbits\": [{}]}}", orbits.join(", ")))
+        }
+        ("contains", [element, ..]) if rest.len() <= 2 => {
+            let (n, gen) = input.read(rest.get(1).copied())?;
+            let g = input.parse_element(n, element)?;
+            let (beta_transversals, _) = incrementally_build_bsgs(n, &[], &gen, &mut rnd);
+            let contains = strip(&g, &beta_transversals).1 == Perm::e(n);
+            Ok(format!("{{\"contains\": {}}}", contains))
+        }
+        ("factor", [element, ..]) if rest.len() <= 2 => {
+            let (n, gen) = input.read(rest.get(1).copied())?;
+            let g = input.parse_element(n, element)?;
+            Ok(match factorization(n, &gen, &g) {
+                Some(word) => format!("{{\"contains\": true, \"word\": {}}}", json_list(&word)),
+                None => "{\"contains\": false, \"word\": null}".to_string(),
+            })
+        }
+        ("is-homomorphism", [domain, image]) => {
+            let (n, gen) = input.read(Some(domain))?;
+            let (m, images) = Input {
+                degree: None,
+                ..input
+            }
+            .read(Some(image))?;
+            if gen.len() != images.len() {
+                return Err(format!(
+                    "{} generators but {} images",
+                    gen.len(),
+                    images.len()
+                ));
+            }
+            let result = is_homomorphism(n, m, &gen, &images);
+            Ok(format!("{{\"is_homomorphism\": {}}}", result))
+        }
+        ("bsgs", [] | [_]) => {
+            let (n, gen) = input.read(rest.first().copied())?;
+            let (beta_transversals, s) = incrementally_build_bsgs(n, &[], &gen, &mut rnd);
+            let s = reduce_strong_generators(n, &beta_transversals, &s);
+            let base: Vec<usize> = beta_transversals.iter().map(|&(beta, _)| beta).collect();
+            let orbit_lengths: Vec<usize> = beta_transversals
+                .iter()
+                .map(|(_, transversal)| transversal.iter().filter(|x| x.is_some()).count())
+                .collect();
+            let s: Vec<String> = s.iter().map(json_perm).collect();
+            Ok(format!(
+                "{{\"degree\": {}, \"order\": {}, \"base\": {}, \"orbit_lengths\": {}, \"strong_generators\": [{}]}}",
+                n,
+                json_bigint(&bsgs_order(&beta_transversals)),
+                json_list(&base),
+                json_list(&orbit_lengths),
+                s.join(", ")
+            ))
+        }
+        ("classes", [] | [_]) => {
+            let (n, gen) = input.read(rest.first().copied())?;
+            let classes: Vec<String> = conjugacy_classes(n, &gen)
+                .ok_or("too many samples to find the conjugacy classes")?
+                .iter()
+                .map(|class| {
+                    format!(
+                        "{{\"representative\": {}, \"order\": {}, \"size\": {}, \"centralizer_order\": {}}}",
+                        json_perm(&class.representative),
+                        json_bigint(&class.representative.order()),
+                        json_bigint(&class.size),
+                        json_bigint(&class.centralizer_order)
+                    )
+                })
+                .collect();
+            Ok(format!("{{\"classes\": [{}]}}", classes.join(", ")))
+        }
+        (
+            "order" | "orbits" | "contains" | "factor" | "is-homomorphism" | "bsgs" | "classes",
+            _,
+        ) => Err(format!(
+            "wrong number of arguments for {} (see --help)",
+            command
+        )),
+        _ => Err(format!("unknown command {:?} (see --help)", command)),
     }
-    e
 }
 
-fn main() {
-    // Rubik's Cube group
-    let n = 48;
-    let mut gen = vec![];
-    let p0 = vec![
-        vec![0, 2, 4, 6],
-        vec![1, 3, 5, 7],
-        vec![14, 16, 34, 28],
-        vec![13, 23, 33, 27],
-        vec![12, 22, 32, 26],
-    ];
-    let p2 = vec![
-        vec![16, 18, 20, 22],
-        vec![17, 19, 21, 23],
-        vec![12, 40, 36, 4],
-        vec![11, 47, 35, 3],
-        vec![10, 46, 34, 2],
-    ];
-    let p1 = vec![
-        vec![8, 10, 12, 14],
-        vec![9, 11, 13, 15],
-        vec![42, 18, 2, 26],
-        vec![41, 17, 1, 25],
-        vec![40, 16, 0, 24],
-    ];
-    let p3 = vec![
-        vec![24, 26, 28, 30],
-        vec![25, 27, 29, 31],
-        vec![8, 0, 32, 44],
-        vec![15, 7, 39, 43],
-        vec![14, 6, 38, 42],
-    ];
-    let p4 = vec![
-        vec![32, 34, 36, 38],
-        vec![33, 35, 37, 39],
-        vec![6, 22, 46, 30],
-        vec![5, 21, 45, 29],
-        vec![4, 20, 44, 28],
-    ];
-    let p5 = vec![
-        vec![40, 42, 44, 46],
-        vec![41, 43, 45, 47],
-        vec![10, 24, 38, 20],
-        vec![9, 31, 37, 19],
-        vec![8, 30, 36, 18],
-    ];
-    for p in &[p0, p1, p2, p3, p4, p5] {
-        gen.push(get_cycle(n, p));
+#[derive(Clone, Copy)]
+enum Format {
+    Plain,
+    Gap,
+    MeatAxe,
+}
+
+#[derive(Clone, Copy)]
+struct Input {
+    degree: Option<usize>,
+    format: Format,
+}
+
+impl Input {
+    /// Reads generators from the file, or from stdin if path is None or "-".
+    fn read(&self, path: Option<&str>) -> Result<(usize, Vec<Perm>), String> {
+        let text = match path {
+            None | Some("-") => {
+                let mut text = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut text)
+                    .map_err(|e| format!("cannot read stdin: {}", e))?;
+                text
+            }
+            Some(path) => {
+                std::fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path, e))?
+            }
+        };
+        match self.format {
+            Format::Plain => parse_generators(&text, self.degree),
+            Format::Gap => parse_gap(&text, self.degree),
+            Format::MeatAxe => {
+                let (m, gen) = parse_meataxe(&text)?;
+                let n = self.degree.unwrap_or(m);
+                if n < m {
+                    return Err(format!("the degree {} is less than {}", n, m));
+                }
+                let gen = gen
+                    .iter()
+                    .map(|g| Perm::new((0..n).map(|i| if i < m { g[i] } else { i }).collect()))
+                    .collect();
+                Ok((n, gen))
+            }
+        }
+    }
+
+    fn parse_element(&self, n: usize, s: &str) -> Result<Perm, String> {
+        match self.format {
+            Format::Gap => match parse_gap(s, Some(n))? {
+                (_, gen) if gen.len() == 1 => Ok(gen[0].clone()),
+                _ => Err(format!("{:?} is not a permutation", s)),
+            },
+            _ => parse_perm(n, s),
+        }
+    }
+}
+
+/// Big integers are written as strings, since JSON parsers usually read numbers as doubles.
+fn json_bigint(x: &BigInt) -> String {
+    format!("\"{}\"", x)
+}
+
+fn json_list<T: ToString>(list: &[T]) -> String {
+    let items: Vec<String> = list.iter().map(|x| x.to_string()).collect();
+    format!("[{}]", items.join(", "))
+}
+
+/// A permutation is written as the list of images.
+fn json_perm(g: &Perm) -> String {
+    let images: Vec<usize> = (0..g.size()).map(|i| g[i]).collect();
+    json_list(&images)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    /// A temporary file that is deleted on drop.
+    struct TempFile(String);
+
+    impl TempFile {
+        /// Writes text to a temporary file.
+        fn new(name: &str, text: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "rust-schreier-test-{}-{}",
+                std::process::id(),
+                name
+            ));
+            std::fs::write(&path, text).unwrap();
+            TempFile(path.to_str().unwrap().to_string())
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    /// Runs the command and parses its output.
+    fn run_json(args: &[&str]) -> Value {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        serde_json::from_str(&run(&args).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn run_test() {
+        // S_3 = <(0 1 2), (0 1)>
+        let s3 = TempFile::new("s3", "# S_3\n(0 1 2)\n[1, 0, 2]\n");
+        assert_eq!(run_json(&["order", &s3.0]), json!({"order": "6"}));
+        assert_eq!(run_json(&["orbits", &s3.0]), json!({"orbits": [[0, 1, 2]]}));
+        assert_eq!(
+            run_json(&["orbits", "-d", "4", &s3.0]),
+            json!({"orbits": [[0, 1, 2], [3]]})
+        );
+        assert_eq!(
+            run_json(&["contains", "(0 2)", &s3.0]),
+            json!({"contains": true})
+        );
+        assert_eq!(
+            run_json(&["contains", "(0 1)", "-d", "4", &s3.0]),
+            json!({"contains": true})
+        );
+        let factor = run_json(&["factor", "(1 2)", &s3.0]);
+        assert_eq!(factor["contains"], json!(true));
+        assert!(factor["word"].is_array());
+        let bsgs = run_json(&["bsgs", &s3.0]);
+        assert_eq!(bsgs["degree"], json!(3));
+        assert_eq!(bsgs["order"], json!("6"));
+        assert!(bsgs["base"].is_array() && bsgs["orbit_lengths"].is_array());
+        assert!(bsgs["strong_generators"][0].is_array());
+        let classes = run_json(&["classes", &s3.0]);
+        let classes = classes["classes"].as_array().unwrap();
+        assert_eq!(classes.len(), 3);
+        assert_eq!(
+            classes[0],
+            json!({"representative": [0, 1, 2], "order": "1", "size": "1", "centralizer_order": "6"})
+        );
+        // The sign of S_3
+        let sign = TempFile::new("sign", "[0, 1]\n[1, 0]\n");
+        assert_eq!(
+            run_json(&["is-homomorphism", &s3.0, &sign.0]),
+            json!({"is_homomorphism": true})
+        );
+        let swapped = TempFile::new("swapped", "[1, 0]\n[0, 1]\n");
+        assert_eq!(
+            run_json(&["is-homomorphism", &s3.0, &swapped.0]),
+            json!({"is_homomorphism": false})
+        );
+    }
+    #[test]
+    fn run_test_formats() {
+        let gap = TempFile::new("gap", "Group((1,2,3), (1,2))");
+        assert_eq!(
+            run_json(&["order", "-f", "gap", &gap.0]),
+            json!({"order": "6"})
+        );
+        assert_eq!(
+            run_json(&["contains", "--format", "gap", "(1,3)", &gap.0]),
+            json!({"contains": true})
+        );
+        let meataxe = TempFile::new("meataxe", "12 1 3 2\n2 3 1\n2 1 3\n");
+        assert_eq!(
+            run_json(&["order", "-f", "meataxe", &meataxe.0]),
+            json!({"order": "6"})
+        );
+    }
+    #[test]
+    fn run_test_errors() {
+        let run_strs = |args: &[&str]| run(&args.iter().map(|s| s.to_string()).collect::<Vec<_>>());
+        assert!(run_strs(&["-h"]).unwrap().starts_with("Usage:"));
+        assert!(run_strs(&[]).is_err());
+        assert!(run_strs(&["unknown"]).is_err());
+        assert!(run_strs(&["is-homomorphism", "a"]).is_err());
+        assert!(run_strs(&["order", "-f", "unknown"]).is_err());
+        assert!(run_strs(&["order", "/nonexistent/file"]).is_err());
+        let s3 = TempFile::new("s3-errors", "(0 1 2)\n(0 1)\n");
+        assert!(run_strs(&["contains", "(0 3)", &s3.0]).is_err());
     }
-    // Outputs order = 43252003274489856000
-    eprintln!("order = {}", order(n, &gen));
 }
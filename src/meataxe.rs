@@ -0,0 +1,115 @@
+use crate::perm::Perm;
+
+// Reference: <https://brauer.maths.qmul.ac.uk/Atlas/v3/>,
+// where a permutation representation is given by one file per generator,
+// e.g. M12G1-p12aB0.m1 and M12G1-p12aB0.m2.
+
+/// Parses permutations in the MeatAxe text format used by the ATLAS of Group Representations.
+/// Returns the degree and the permutations, with 1-based images converted to 0-based ones.
+///
+/// Two headers are accepted: `permutation degree=n`, followed by the images of one
+/// permutation, and the older `12 1 n k`, followed by the images of k permutations.
+/// Several files may be concatenated, as long as their degrees agree.
+pub fn parse_meataxe(text: &str) -> Result<(usize, Vec<Perm>), String> {
+    let mut degree = None;
+    let mut gen = vec![];
+    let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+    while let Some(header) = lines.next() {
+        let (n, count) = parse_header(header.trim())?;
+        if degree.is_some_and(|m| m != n) {
+            return Err(format!("degrees {} and {} differ", degree.unwrap(), n));
+        }
+        degree = Some(n);
+        let total = n
+            .checked_mul(count)
+            .ok_or_else(|| format!("too many images in the header {:?}", header.trim()))?;
+        let mut images = vec![];
+        while images.len() < total {
+            let Some(line) = lines.next() else {
+                return Err(format!("expected {} images, found {}", total, images.len()));
+            };
+            for x in line.split_whitespace() {
+                match x.parse::<usize>() {
+                    Ok(x) if (1..=n).contains(&x) => images.push(x - 1),
+                    _ => return Err(format!("invalid point {:?}", x)),
+                }
+            }
+        }
+        if images.len() != total {
+            return Err(format!("expected {} images, found {}", total, images.len()));
+        }
+        for images in images.chunks(n.max(1)).take(count) {
+            let mut seen = vec![false; n];
+            for &x in images {
+                if seen[x] {
+                    return Err(format!("point {} appears twice in a permutation", x + 1));
+                }
+                seen[x] = true;
+            }
+            gen.push(Perm::from_vec_unchecked(images.to_vec()));
+        }
+    }
+    Ok((degree.ok_or("empty MeatAxe file")?, gen))
+}
+
+/// Returns g in the MeatAxe text format with the header `permutation degree=n`.
+pub fn to_meataxe(g: &Perm) -> String {
+    let mut result = format!("permutation degree={}\n", g.size());
+    for i in 0..g.size() {
+        result += &format!("{}\n", g[i] + 1);
+    }
+    result
+}
+
+/// Returns the degree and the number of permutations following the header.
+fn parse_header(header: &str) -> Result<(usize, usize), String> {
+    if let Some(rest) = header.strip_prefix("permutation") {
+        let degree = rest
+            .split_whitespace()
+            .find_map(|field| field.strip_prefix("degree="))
+            .ok_or_else(|| format!("no degree in the header {:?}", header))?;
+        let n = degree
+            .parse()
+            .map_err(|_| format!("invalid degree {:?}", degree))?;
+        return Ok((n, 1));
+    }
+    let fields: Vec<usize> = header
+        .split_whitespace()
+        .map(|x| x.parse())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("invalid header {:?}", header))?;
+    match fields[..] {
+        [12, _, n, count] => Ok((n, count)),
+        _ => Err(format!("not a permutation header {:?}", header)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groups::mathieu12;
+
+    #[test]
+    fn parse_meataxe_test() {
+        let (n, gen) = mathieu12::generators();
+        // The first standard generator of M12 in the ATLAS representation M12G1-p12aB0
+        let text = "permutation degree=12\n4\n2\n10\n1\n11\n12\n7\n8\n9\n3\n5\n6\n";
+        assert_eq!(parse_meataxe(text), Ok((n, vec![gen[0].clone()])));
+        assert_eq!(
+            parse_meataxe(&to_meataxe(&gen[1])),
+            Ok((n, vec![gen[1].clone()]))
+        );
+        let text = "12 1 3 2\n2 3 1\n2 1 3\n";
+        assert_eq!(
+            parse_meataxe(text),
+            Ok((3, vec![Perm::new(vec![1, 2, 0]), Perm::new(vec![1, 0, 2])]))
+        );
+        assert!(parse_meataxe("permutation degree=3\n1\n1\n2\n").is_err());
+        assert!(parse_meataxe("permutation degree=3\n1\n2\n").is_err());
+        assert!(parse_meataxe("1 1 3 3\n").is_err());
+        assert!(parse_meataxe("12 1 18446744073709551615 2\n").is_err());
+        // Concatenated files
+        let text = to_meataxe(&gen[0]) + &to_meataxe(&gen[1]);
+        assert_eq!(parse_meataxe(&text), Ok((n, gen)));
+    }
+}
@@ -0,0 +1,229 @@
+use crate::backtrack::subgroup_search;
+use crate::blocks::{minimal_block, part_sizes};
+use crate::perm::Perm;
+use crate::schreier::{incrementally_build_bsgs, strip};
+use crate::transversal::{orbit, orbit_transversal_stabilizer};
+use std::collections::{HashMap, HashSet};
+
+/// Returns a generator set of N_G(H), where G = \<g\> and H = \<h\>
+/// are subgroups of the symmetric group of degree n.
+///
+/// An element x of N_G(H) maps the orbits, point stabilizers and block systems
+/// of H to those of H, so it preserves a coloring of the pairs of points derived
+/// from them. The search runs over the base images of the BSGS of G. At each node,
+/// the base points and their images are individualized in two ordered partitions,
+/// both are refined against the pair coloring until they are equitable,
+/// and the node is pruned when the refined partitions differ.
+pub fn normalizer(n: usize, g: &[Perm], h: &[Perm]) -> Vec<Perm> {
+    let mut rnd = rand::thread_rng();
+    let (g_transversals, _) = incrementally_build_bsgs(n, &[], g, &mut rnd);
+    let (h_transversals, _) = incrementally_build_bsgs(n, &[], h, &mut rnd);
+    let in_h = |x: &Perm| strip(x, &h_transversals).1 == Perm::e(n);
+    let base: Vec<usize> = g_transversals.iter().map(|&(beta, _)| beta).collect();
+    let invariants = Invariants::new(n, h, &base);
+    // Elements of G ∩ H are known to be in N_G(H).
+    let known: Vec<Perm> = h
+        .iter()
+        .filter(|x| strip(x, &g_transversals).1 == Perm::e(n))
+        .cloned()
+        .collect();
+    subgroup_search(
+        n,
+        &g_transversals,
+        &known,
+        |x| {
+            let x_inv = x.inv();
+            h.iter().all(|y| in_h(&x_inv.compose(y).compose(x)))
+        },
+        |images| invariants.is_consistent(images),
+    )
+}
+
+struct Invariants {
+    /// Points with different cells are not mapped to each other.
+    cell: Vec<usize>,
+    /// pair[a][b] is an invariant of (a, b) under the normalizer.
+    pair: Vec<Vec<(usize, usize, usize)>>,
+    /// arc\[a\]\[b\] labels the pair invariants of (a, b) and (b, a).
+    arc: Vec<Vec<usize>>,
+    /// The base of G and the refined partitions of its prefixes
+    base: Vec<usize>,
+    base_cells: Vec<Vec<usize>>,
+}
+
+impl Invariants {
+    fn new(n: usize, h: &[Perm], base: &[usize]) -> Self {
+        let mut orbit_id = vec![0; n];
+        let mut orbit_len = vec![0; n];
+        for a in (0..n).rev() {
+            let o = orbit(n, h, a);
+            for &b in &o {
+                orbit_id[b] = a;
+                orbit_len[b] = o.len();
+            }
+        }
+        // pair[a][b] = (whether a and b are in the same orbit of H,
+        //               |b^{H_a}|,
+        //               the size of the minimal block containing a and b)
+        let mut pair = vec![vec![(0, 0, 0); n]; n];
+        for a in 0..n {
+            let (_, stab) = orbit_transversal_stabilizer(n, h, a);
+            for b in 0..n {
+                let same = (orbit_id[a] == orbit_id[b]) as usize;
+                pair[a][b] = (same, orbit(n, &stab, b).len(), 0);
+            }
+            for b in 0..n {
+                if orbit_id[a] == orbit_id[b] && a != b {
+                    let block = part_sizes(&minimal_block(n, h, a, b));
+                    pair[a][b].2 = block[a];
+                }
+            }
+        }
+        // Points are refined by their orbit lengths and the multisets of pair invariants.
+        let mut cell_id = HashMap::new();
+        let mut cell = vec![0; n];
+        for a in 0..n {
+            let mut profile = pair[a].clone();
+            profile.sort_unstable();
+            let next = cell_id.len();
+            cell[a] = *cell_id.entry((orbit_len[a], profile)).or_insert(next);
+        }
+        let mut arc_id = HashMap::new();
+        let arc = (0..n)
+            .map(|a| {
+                (0..n)
+                    .map(|b| {
+                        let next = arc_id.len();
+                        *arc_id.entry((pair[a][b], pair[b][a])).or_insert(next)
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut invariants = Invariants {
+            cell,
+            pair,
+            arc,
+            base: base.to_vec(),
+            base_cells: vec![],
+        };
+        invariants.base_cells = (0..=base.len())
+            .map(|k| invariants.refine(&base[..k]))
+            .collect();
+        invariants
+    }
+    /// Individualizes the points in order and refines the cells until every point
+    /// of a cell sees the same multiset of (cell, pair invariant) over all points.
+    ///
+    /// Cells are labeled canonically, so an element of the normalizer mapping
+    /// points\[i\] to the i-th point of another call maps the result of this call
+    /// to the result of the other.
+    fn refine(&self, points: &[usize]) -> Vec<usize> {
+        let n = self.cell.len();
+        let mut color = self.cell.clone();
+        let mut individual = vec![0; n];
+        for (i, &a) in points.iter().enumerate() {
+            individual[a] = i + 1;
+        }
+        let mut count = (0..n)
+            .map(|a| (color[a], individual[a]))
+            .collect::<HashSet<_>>()
+            .len();
+        loop {
+            let signatures: Vec<_> = (0..n)
+                .map(|a| {
+                    let mut seen: Vec<_> = (0..n).map(|b| (color[b], self.arc[a][b])).collect();
+                    seen.sort_unstable();
+                    (color[a], individual[a], seen)
+                })
+                .collect();
+            let mut labels: Vec<_> = signatures.iter().collect();
+            labels.sort_unstable();
+            labels.dedup();
+            color = signatures
+                .iter()
+                .map(|x| labels.binary_search(&x).unwrap())
+                .collect();
+            if labels.len() == count {
+                return color;
+            }
+            count = labels.len();
+        }
+    }
+    fn is_consistent(&self, images: &[(usize, usize)]) -> bool {
+        for (i, &(a, c)) in images.iter().enumerate() {
+            if self.cell[a] != self.cell[c] {
+                return false;
+            }
+            for &(b, d) in &images[..i] {
+                if self.pair[a][b] != self.pair[c][d] || self.pair[b][a] != self.pair[d][c] {
+                    return false;
+                }
+            }
+        }
+        let (left, right): (Vec<usize>, Vec<usize>) = images.iter().cloned().unzip();
+        // The search always individualizes a prefix of the base on the left.
+        let owned;
+        let left = if self.base.starts_with(&left) {
+            &self.base_cells[left.len()]
+        } else {
+            owned = self.refine(&left);
+            &owned
+        };
+        let right = self.refine(&right);
+        // The cells of the two partitions must have the same sizes,
+        // and each point must be in the cell of its image.
+        let mut sizes = (vec![0; left.len()], vec![0; right.len()]);
+        for a in 0..left.len() {
+            sizes.0[left[a]] += 1;
+            sizes.1[right[a]] += 1;
+        }
+        sizes.0 == sizes.1 && images.iter().all(|&(a, c)| left[a] == right[c])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groups::{mathieu12, symmetric};
+    use crate::schreier::order;
+
+    #[test]
+    fn normalizer_test_cyclic() {
+        // N_{S_5}(<(0 1 2 3 4)>) = AGL(1, 5), |AGL(1, 5)| = 20
+        let n = 5;
+        let h = vec![Perm::new(vec![1, 2, 3, 4, 0])];
+        assert_eq!(
+            order(n, &normalizer(n, &symmetric::generators(n).1, &h)),
+            20.into()
+        );
+        // N_{S_4}(<(0 1)>) = <(0 1), (2 3)>
+        let n = 4;
+        let h = vec![Perm::new(vec![1, 0, 2, 3])];
+        assert_eq!(
+            order(n, &normalizer(n, &symmetric::generators(n).1, &h)),
+            4.into()
+        );
+    }
+    #[test]
+    fn normalizer_test_affine() {
+        // The translations of F_2^3 are normalized by AGL(3, 2), |AGL(3, 2)| = 8 * 168
+        let n = 8;
+        let h: Vec<Perm> = [1, 2, 4]
+            .iter()
+            .map(|&t| Perm::new((0..n).map(|x| x ^ t).collect()))
+            .collect();
+        assert_eq!(
+            order(n, &normalizer(n, &symmetric::generators(n).1, &h)),
+            1344.into()
+        );
+    }
+    #[test]
+    fn normalizer_test_m12() {
+        // M12 is self-normalizing in S_12.
+        let (n, gen) = mathieu12::generators();
+        assert_eq!(
+            order(n, &normalizer(n, &symmetric::generators(n).1, &gen)),
+            95040.into()
+        );
+    }
+}
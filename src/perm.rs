@@ -1,83 +1,285 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// The type of the points of a permutation. Narrower types make permutations of small degree
+/// smaller, so that transversal tables of large groups take less memory.
+pub trait Point: Copy + Eq + Ord + Hash + Debug + Send + Sync + 'static {
+    /// The largest degree of permutations of this point type
+    const MAX_DEGREE: usize;
+    /// x must be less than MAX_DEGREE.
+    fn from_usize(x: usize) -> Self;
+    fn to_usize(self) -> usize;
+}
+
+macro_rules! impl_point {
+    ($($t:ty),*) => {
+        $(
+            impl Point for $t {
+                // u32::MAX + 1 does not fit in a 32-bit usize.
+                const MAX_DEGREE: usize = (<$t>::MAX as usize).saturating_add(1);
+                #[inline]
+                fn from_usize(x: usize) -> Self {
+                    x as $t
+                }
+                #[inline]
+                fn to_usize(self) -> usize {
+                    self as usize
+                }
+            }
+        )*
+    };
+}
+
+impl_point!(u8, u16, u32);
+
+impl Point for usize {
+    const MAX_DEGREE: usize = usize::MAX;
+    #[inline]
+    fn from_usize(x: usize) -> Self {
+        x
+    }
+    #[inline]
+    fn to_usize(self) -> usize {
+        self
+    }
+}
+
+/// A permutation of the points 0..n, stored as the list of images.
+/// `Perm<u8>`, `Perm<u16>` and `Perm<u32>` hold permutations of degree at most 2^8, 2^16 and 2^32.
 #[derive(Clone, Eq, PartialEq, Debug, PartialOrd, Ord)]
-pub struct Perm(Vec<usize>);
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "Vec<usize>", into = "Vec<usize>")
+)]
+pub struct Perm<P: Point = usize>(Vec<P>);
 impl Perm {
     pub fn new(perm: Vec<usize>) -> Perm {
+        Self::from_images(perm)
+    }
+    pub fn e(n: usize) -> Self {
+        Self::identity(n)
+    }
+}
+impl<P: Point> Perm<P> {
+    /// The same as new, for any point type
+    pub fn from_images(perm: Vec<usize>) -> Self {
         let n = perm.len();
+        assert!(n <= P::MAX_DEGREE);
         let mut appear = vec![false; n];
         for &p in &perm {
             assert!(!appear[p]);
             appear[p] = true;
         }
-        Perm(perm)
+        Perm(perm.into_iter().map(P::from_usize).collect())
     }
     pub fn size(&self) -> usize {
         let Perm(x) = self;
         x.len()
     }
+    /// Returns the image of i.
+    #[inline]
+    pub fn image(&self, i: usize) -> usize {
+        self.0[i].to_usize()
+    }
+    /// Returns the list of images.
+    pub fn images(&self) -> &[P] {
+        &self.0
+    }
+    /// Returns the same permutation with another point type.
+    pub fn cast<Q: Point>(&self) -> Perm<Q> {
+        assert!(self.size() <= Q::MAX_DEGREE);
+        Perm::from_vec_unchecked(
+            self.0
+                .iter()
+                .map(|&x| Q::from_usize(x.to_usize()))
+                .collect(),
+        )
+    }
     pub fn compose(&self, Perm(other): &Self) -> Self {
         let Perm(me) = self;
         let n = self.size();
         assert_eq!(n, other.len());
-        let mut ans = vec![0; n];
-        for i in 0..n {
-            ans[i] = other[me[i]];
+        Self::from_vec_unchecked(me.iter().map(|&x| other[x.to_usize()]).collect())
+    }
+    /// The same as e, for any point type
+    pub fn identity(n: usize) -> Self {
+        Perm((0..n).map(P::from_usize).collect())
+    }
+    /// Wraps images without checking that they form a permutation.
+    pub(crate) fn from_vec_unchecked(images: Vec<P>) -> Self {
+        Perm(images)
+    }
+    pub fn is_identity(&self) -> bool {
+        self.0.iter().enumerate().all(|(i, &x)| x.to_usize() == i)
+    }
+    /// Sets out to self.compose(other), reusing the storage of out.
+    pub fn compose_into(&self, other: &Self, out: &mut Self) {
+        assert_eq!(self.size(), other.size());
+        out.0.clear();
+        out.0.extend(self.0.iter().map(|&x| other.0[x.to_usize()]));
+    }
+    /// Sets out to self.inv(), reusing the storage of out.
+    pub fn inv_into(&self, out: &mut Self) {
+        let n = self.size();
+        out.0.resize(n, P::from_usize(0));
+        for (i, &x) in self.0.iter().enumerate() {
+            out.0[x.to_usize()] = P::from_usize(i);
         }
-        Self::new(ans)
     }
-    pub fn e(n: usize) -> Self {
-        Self::new((0..n).collect())
+    /// Replaces self with self.compose(other) in place.
+    pub fn mul_assign(&mut self, other: &Self) {
+        assert_eq!(self.size(), other.size());
+        for x in self.0.iter_mut() {
+            *x = other.0[x.to_usize()];
+        }
     }
     pub fn inv(&self) -> Self {
         let Perm(me) = self;
         let n = self.size();
-        let mut ans = vec![0; n];
+        let mut ans = vec![P::from_usize(0); n];
         for i in 0..n {
-            ans[me[i]] = i;
+            ans[me[i].to_usize()] = P::from_usize(i);
         }
-        Self::new(ans)
+        Self::from_vec_unchecked(ans)
     }
     pub fn pow(&self, k: i64) -> Self {
         let n = self.size();
-        let mut sum = Self::e(n);
+        let mut sum = Self::identity(n);
         if k == 0 {
             return sum;
         }
         let mut cur = if k < 0 { self.inv() } else { self.clone() };
+        let mut tmp = Self::identity(n);
         let mut k = k.abs();
         while k > 0 {
             if (k & 1) == 1 {
-                sum = sum.compose(&cur);
+                sum.mul_assign(&cur);
             }
-            cur = cur.compose(&cur);
+            cur.compose_into(&cur, &mut tmp);
+            std::mem::swap(&mut cur, &mut tmp);
             k >>= 1;
         }
         sum
     }
-    /// Returns a new permutation of self.size() + a.size().
-    pub fn concat(&self, a: &Perm) -> Perm {
+    /// Returns the lengths of the cycles (including fixed points) in ascending order.
+    pub fn cycle_type(&self) -> Vec<usize> {
         let n = self.size();
-        let m = a.size();
-        let mut v = vec![0; n + m];
-
+        let mut visited = vec![false; n];
+        let mut lengths = vec![];
         for i in 0..n {
-            v[i] = self[i];
-        }
-        for i in 0..m {
-            v[n + i] = n + a[i];
+            let mut len = 0;
+            let mut j = i;
+            while !visited[j] {
+                visited[j] = true;
+                j = self.image(j);
+                len += 1;
+            }
+            if len > 0 {
+                lengths.push(len);
+            }
         }
-        Perm::new(v)
+        lengths.sort_unstable();
+        lengths
+    }
+    /// Returns the order of self, the least common multiple of the cycle lengths.
+    pub fn order(&self) -> num_bigint::BigInt {
+        let mut lengths = self.cycle_type();
+        lengths.dedup();
+        lengths
+            .into_iter()
+            .fold(1.into(), |acc, len| crate::integer::lcm(&acc, &len.into()))
+    }
+    /// Returns a new permutation of self.size() + a.size().
+    pub fn concat(&self, a: &Self) -> Self {
+        let n = self.size();
+        let m = a.size();
+        let v = (0..n)
+            .map(|i| self.image(i))
+            .chain((0..m).map(|i| n + a.image(i)))
+            .collect();
+        Perm::from_images(v)
+    }
+    /// The inverse of concat: splits self into the actions on 0..n and n..size().
+    /// self must map 0..n onto itself.
+    pub fn split(&self, n: usize) -> (Self, Self) {
+        let Perm(me) = self;
+        let left = me[..n].iter().map(|&x| x.to_usize()).collect();
+        let right = me[n..].iter().map(|&x| x.to_usize() - n).collect();
+        (Perm::from_images(left), Perm::from_images(right))
     }
 }
 
-impl std::ops::Index<usize> for Perm {
-    type Output = usize;
+impl<P: Point> std::ops::Index<usize> for Perm<P> {
+    type Output = P;
 
-    fn index(&self, index: usize) -> &usize {
+    fn index(&self, index: usize) -> &P {
         let Perm(x) = &self;
         &x[index]
     }
 }
 
+/// a * b = a.compose(b): a is applied first.
+impl<P: Point> std::ops::Mul<&Perm<P>> for &Perm<P> {
+    type Output = Perm<P>;
+    fn mul(self, rhs: &Perm<P>) -> Perm<P> {
+        self.compose(rhs)
+    }
+}
+
+impl<P: Point> std::ops::Mul<&Perm<P>> for Perm<P> {
+    type Output = Perm<P>;
+    fn mul(mut self, rhs: &Perm<P>) -> Perm<P> {
+        Perm::mul_assign(&mut self, rhs);
+        self
+    }
+}
+
+impl<P: Point> std::ops::Mul for Perm<P> {
+    type Output = Perm<P>;
+    fn mul(mut self, rhs: Perm<P>) -> Perm<P> {
+        Perm::mul_assign(&mut self, &rhs);
+        self
+    }
+}
+
+impl<P: Point> std::ops::MulAssign<&Perm<P>> for Perm<P> {
+    fn mul_assign(&mut self, rhs: &Perm<P>) {
+        Perm::mul_assign(self, rhs);
+    }
+}
+
+impl<P: Point> std::ops::MulAssign for Perm<P> {
+    fn mul_assign(&mut self, rhs: Perm<P>) {
+        Perm::mul_assign(self, &rhs);
+    }
+}
+
+/// Checks that the images form a permutation of 0..len.
+impl<P: Point> TryFrom<Vec<usize>> for Perm<P> {
+    type Error = String;
+    fn try_from(images: Vec<usize>) -> Result<Self, Self::Error> {
+        let n = images.len();
+        if n > P::MAX_DEGREE {
+            return Err(format!("the degree {} is too large", n));
+        }
+        let mut appear = vec![false; n];
+        for &p in &images {
+            if p >= n || appear[p] {
+                return Err(format!("{:?} is not a permutation", images));
+            }
+            appear[p] = true;
+        }
+        Ok(Perm(images.into_iter().map(P::from_usize).collect()))
+    }
+}
+
+impl<P: Point> From<Perm<P>> for Vec<usize> {
+    fn from(Perm(images): Perm<P>) -> Self {
+        images.into_iter().map(P::to_usize).collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -94,4 +296,56 @@ mod tests {
         // p^{-3} = p^2 = pp
         assert_eq!(p.pow(-3), p.compose(&p));
     }
+    #[test]
+    fn perm_cycle_type_test() {
+        // (0 1)(2 4 5)
+        let p = Perm::new(vec![1, 0, 4, 3, 5, 2]);
+        assert_eq!(p.cycle_type(), vec![1, 2, 3]);
+        assert_eq!(Perm::e(3).cycle_type(), vec![1, 1, 1]);
+    }
+    #[test]
+    fn perm_order_test() {
+        // (0 1)(2 4 5)
+        let p = Perm::new(vec![1, 0, 4, 3, 5, 2]);
+        assert_eq!(p.order(), 6.into());
+        assert_eq!(Perm::e(3).order(), 1.into());
+    }
+    #[test]
+    fn perm_split_test() {
+        let a = Perm::new(vec![1, 0, 2]);
+        let b = Perm::new(vec![1, 2, 0]);
+        assert_eq!(a.concat(&b).split(3), (a, b));
+    }
+    #[test]
+    fn perm_point_type_test() {
+        let p: Perm<u8> = Perm::from_images(vec![1, 0, 4, 3, 5, 2]);
+        assert_eq!(p.image(2), 4);
+        assert_eq!(p.compose(&p.inv()), Perm::identity(6));
+        assert_eq!(p.cast::<usize>(), Perm::new(vec![1, 0, 4, 3, 5, 2]));
+        assert_eq!(p.cast::<u16>().order(), 6.into());
+        assert!(Perm::<u8>::try_from((0..257).collect::<Vec<_>>()).is_err());
+        assert!(Perm::<u8>::try_from((0..256).collect::<Vec<_>>()).is_ok());
+        assert_eq!(u16::MAX_DEGREE, 1 << 16);
+        assert!(u32::MAX_DEGREE >= u32::MAX as usize);
+    }
+    #[test]
+    fn perm_in_place_test() {
+        let a = Perm::new(vec![1, 2, 0, 3]);
+        let b = Perm::new(vec![0, 1, 3, 2]);
+        let mut out = Perm::e(1);
+        a.compose_into(&b, &mut out);
+        assert_eq!(out, a.compose(&b));
+        a.inv_into(&mut out);
+        assert_eq!(out, a.inv());
+        assert!(a.compose(&out).is_identity());
+        assert_eq!(&a * &b, a.compose(&b));
+        assert_eq!(a.clone() * &b, a.compose(&b));
+        assert_eq!(a.clone() * b.clone(), a.compose(&b));
+        let mut c = a.clone();
+        c *= &b;
+        c *= b.clone();
+        assert_eq!(c, a);
+        c.mul_assign(&a);
+        assert_eq!(c, a.pow(2));
+    }
 }
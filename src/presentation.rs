@@ -0,0 +1,165 @@
+use crate::fpgroup::{free_reduce, inverse, FpGroup, Word};
+use crate::perm::Perm;
+use crate::schreier::{incrementally_build_bsgs, strip};
+use crate::transversal::{get_transversal, Transversal};
+
+// Reference: D. F. Holt, B. Eick, E. A. O'Brien, Handbook of Computational Group Theory,
+// Sections 4.4 and 6.1.
+
+/// A level of a stabilizer chain whose representatives are also kept as words
+/// in the strong generators.
+struct Level {
+    beta: usize,
+    transversal: Transversal,
+    /// words\[gamma\] is a word for transversal\[gamma\].
+    words: Vec<Option<Word>>,
+}
+
+/// Returns a presentation of G = \<gen\>, together with the images of its generators.
+///
+/// The generators of the presentation are all the strong generators of a BSGS built by
+/// the Schreier-Sims algorithm, the first gen.len() of which are gen themselves.
+/// The relators are the Schreier relators u_gamma s = u_{gamma^s} v, where
+/// u_gamma is a transversal word of a level and v is the word obtained by sifting
+/// u_gamma s u_{gamma^s}^{-1} through the levels below.
+pub fn presentation(n: usize, gen: &[Perm]) -> (FpGroup, Vec<Perm>) {
+    let mut rnd = rand::thread_rng();
+    let (beta_transversals, strong) = incrementally_build_bsgs(n, &[], gen, &mut rnd);
+    let base: Vec<usize> = beta_transversals.iter().map(|&(beta, _)| beta).collect();
+    let levels = compute_levels(n, &base, &strong);
+    let chain: Vec<(usize, Transversal)> = levels
+        .iter()
+        .map(|level| (level.beta, level.transversal.clone()))
+        .collect();
+    let mut relators = vec![];
+    for i in 0..levels.len() {
+        for (y, word) in schreier_generators(&levels, &strong, i) {
+            // y u_{i+1}^{-1} u_{i+2}^{-1} ... = h is the identity for a BSGS.
+            let (us, h) = strip(&y, &chain[i + 1..]);
+            debug_assert_eq!(h, Perm::e(n));
+            let mut relator = word;
+            for (u, level) in us.iter().zip(&levels[i + 1..]) {
+                relator.extend(inverse(level.words[u[level.beta]].as_ref().unwrap()));
+            }
+            let relator = free_reduce(&relator);
+            if !relator.is_empty() {
+                relators.push(relator);
+            }
+        }
+    }
+    // Strong generators fixing every base point are the identity.
+    for (a, s) in strong.iter().enumerate() {
+        if base.iter().all(|&beta| s[beta] == beta) {
+            relators.push(vec![a as i32 + 1]);
+        }
+    }
+    (FpGroup::new(strong.len(), relators), strong)
+}
+
+/// Returns the levels of the stabilizer chain with the base and the strong generators,
+/// whose representatives are found by breadth-first search, so that the words are short.
+fn compute_levels(n: usize, base: &[usize], strong: &[Perm]) -> Vec<Level> {
+    let mut levels = vec![];
+    for (i, &beta) in base.iter().enumerate() {
+        let stabilizer: Vec<usize> = (0..strong.len())
+            .filter(|&a| base[..i].iter().all(|&b| strong[a][b] == b))
+            .collect();
+        let mut reprs: Vec<Option<Perm>> = vec![None; n];
+        let mut words: Vec<Option<Word>> = vec![None; n];
+        reprs[beta] = Some(Perm::e(n));
+        words[beta] = Some(vec![]);
+        let mut orbit = vec![beta];
+        let mut pos = 0;
+        while pos < orbit.len() {
+            let w = orbit[pos];
+            pos += 1;
+            for &a in &stabilizer {
+                let x = strong[a][w];
+                if reprs[x].is_none() {
+                    reprs[x] = Some(reprs[w].as_ref().unwrap().compose(&strong[a]));
+                    words[x] = Some([&words[w].as_ref().unwrap()[..], &[a as i32 + 1]].concat());
+                    orbit.push(x);
+                }
+            }
+        }
+        let orbit_transversal = orbit
+            .into_iter()
+            .map(|x| (x, reprs[x].take().unwrap()))
+            .collect();
+        levels.push(Level {
+            beta,
+            transversal: get_transversal(n, orbit_transversal),
+            words,
+        });
+    }
+    levels
+}
+
+/// Returns the Schreier generators u_gamma s u_{gamma^s}^{-1} of level i with their words.
+fn schreier_generators(levels: &[Level], strong: &[Perm], i: usize) -> Vec<(Perm, Word)> {
+    let level = &levels[i];
+    let mut result = vec![];
+    for (gamma, repr) in level.transversal.iter().enumerate() {
+        let Some(u) = repr else {
+            continue;
+        };
+        let u_word = level.words[gamma].as_ref().unwrap();
+        for (a, s) in strong.iter().enumerate() {
+            if levels[..i].iter().any(|l| s[l.beta] != l.beta) {
+                continue;
+            }
+            let delta = s[gamma];
+            let v_inv = level.transversal.inverse(delta).unwrap();
+            let v_word = level.words[delta].as_ref().unwrap();
+            let y = u.compose(s).compose(v_inv);
+            let word = [&u_word[..], &[a as i32 + 1], &inverse(v_word)].concat();
+            result.push((y, word));
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fpgroup::evaluate_word;
+    use crate::groups::{mathieu12, rubik};
+
+    #[test]
+    fn presentation_test_s4() {
+        let n = 4;
+        let gen = vec![Perm::new(vec![1, 2, 3, 0]), Perm::new(vec![1, 0, 2, 3])];
+        let (g, images) = presentation(n, &gen);
+        assert_eq!(&images[..2], &gen[..]);
+        // Todd-Coxeter verifies that the presentation defines a group of order 24.
+        assert_eq!(g.order(10000), Some(24.into()));
+    }
+    #[test]
+    fn presentation_test_m12() {
+        let (n, gen) = mathieu12::generators();
+        let (g, images) = presentation(n, &gen);
+        for r in &g.relators {
+            assert_eq!(evaluate_word(n, &images, r), Perm::e(n));
+        }
+    }
+    #[test]
+    fn presentation_test_psl27() {
+        // PSL(2, 7) = <(0 1 2 3 4 5 6), (2 4)(5 6)>, the collineations of the Fano plane
+        let n = 7;
+        let gen = vec![
+            Perm::new(vec![1, 2, 3, 4, 5, 6, 0]),
+            Perm::new(vec![0, 1, 4, 3, 2, 6, 5]),
+        ];
+        let (g, _) = presentation(n, &gen);
+        assert_eq!(g.order(100000), Some(168.into()));
+    }
+    #[test]
+    fn presentation_test_rubik() {
+        let (n, gen) = rubik::generators();
+        let (g, images) = presentation(n, &gen);
+        assert_eq!(&images[..gen.len()], &gen[..]);
+        for r in &g.relators {
+            assert_eq!(evaluate_word(n, &images, r), Perm::e(n));
+        }
+    }
+}
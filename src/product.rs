@@ -0,0 +1,325 @@
+use crate::homomorphism::Homomorphism;
+use crate::perm::Perm;
+use crate::schreier::{incrementally_build_bsgs, strip};
+use crate::transversal::orbit;
+
+/// A group constructed from other groups, with the homomorphisms relating them.
+pub struct Product {
+    pub degree: usize,
+    pub generators: Vec<Perm>,
+    /// Embeddings of the factors into the product
+    pub embeddings: Vec<Homomorphism>,
+    /// Projections of the product onto the factors
+    pub projections: Vec<Homomorphism>,
+}
+
+impl Product {
+    /// Builds a product whose generators are the images of the generators of the factors,
+    /// so that the embeddings and projections map generators to generators.
+    /// images\[i\] are the images of the generators of factors\[i\] in the product.
+    fn new(degree: usize, factors: &[(usize, Vec<Perm>)], images: Vec<Vec<Perm>>) -> Self {
+        let generators: Vec<Perm> = images.concat();
+        let mut embeddings = vec![];
+        let mut projections = vec![];
+        for (i, ((n, gen), image)) in factors.iter().zip(&images).enumerate() {
+            embeddings.push(Homomorphism::new(*n, degree, gen, image).unwrap());
+            // The generators of the other factors are in the kernel.
+            let projected: Vec<Perm> = images
+                .iter()
+                .enumerate()
+                .flat_map(|(j, image)| {
+                    if i == j {
+                        gen.clone()
+                    } else {
+                        vec![Perm::e(*n); image.len()]
+                    }
+                })
+                .collect();
+            projections.push(Homomorphism::new(degree, *n, &generators, &projected).unwrap());
+        }
+        Product {
+            degree,
+            generators,
+            embeddings,
+            projections,
+        }
+    }
+}
+
+/// Returns the direct product of the factors (n_i, gen_i) acting on the disjoint union
+/// of their points, of degree n_0 + n_1 + ....
+pub fn direct_product(factors: &[(usize, Vec<Perm>)]) -> Product {
+    let degree = factors.iter().map(|(n, _)| n).sum();
+    let mut offset = 0;
+    let mut images = vec![];
+    for (n, gen) in factors {
+        let (before, after) = (Perm::e(offset), Perm::e(degree - offset - n));
+        images.push(
+            gen.iter()
+                .map(|g| before.concat(g).concat(&after))
+                .collect(),
+        );
+        offset += n;
+    }
+    Product::new(degree, factors, images)
+}
+
+/// Returns the direct product of the factors (n_i, gen_i) in product action on the tuples
+/// (x_0, x_1, ...), of degree n_0 n_1 .... The tuple is numbered as a mixed-radix number
+/// whose most significant digit is x_0.
+pub fn direct_product_product_action(factors: &[(usize, Vec<Perm>)]) -> Product {
+    let radices: Vec<usize> = factors.iter().map(|&(n, _)| n).collect();
+    let degree = radices.iter().product();
+    let images = factors
+        .iter()
+        .enumerate()
+        .map(|(i, (_, gen))| {
+            gen.iter()
+                .map(|g| coordinate_action(&radices, |x| x[i] = g[x[i]]))
+                .collect()
+        })
+        .collect();
+    Product::new(degree, factors, images)
+}
+
+/// Returns the wreath product G wr H in imprimitive action on k blocks of n points,
+/// where G = \<g\> is of degree n and H = \<h\> is of degree k. The point x of block b is b n + x.
+///
+/// The embeddings are those of G into each of the k blocks, followed by that of H.
+/// The only projection is onto H.
+pub fn wreath_product(n: usize, g: &[Perm], k: usize, h: &[Perm]) -> Product {
+    let degree = n * k;
+    let block_action = |b: usize, x: &Perm| {
+        let mut image: Vec<usize> = (0..degree).collect();
+        for i in 0..n {
+            image[b * n + i] = b * n + x[i];
+        }
+        Perm::new(image)
+    };
+    let top: Vec<Perm> = h
+        .iter()
+        .map(|y| Perm::new((0..degree).map(|p| y[p / n] * n + p % n).collect()))
+        .collect();
+    wreath(n, g, k, h, degree, block_action, top)
+}
+
+/// Returns the wreath product G wr H in product action on the n^k tuples (x_0, ..., x_{k-1}),
+/// where G = \<g\> is of degree n and H = \<h\> is of degree k. H permutes the coordinates:
+/// the coordinate b of a tuple is moved to the coordinate b^y by y in H.
+///
+/// The embeddings and the projection are as in wreath_product.
+pub fn wreath_product_product_action(n: usize, g: &[Perm], k: usize, h: &[Perm]) -> Product {
+    let radices = vec![n; k];
+    let degree = n.pow(k as u32);
+    let block_action = |b: usize, x: &Perm| coordinate_action(&radices, |t| t[b] = x[t[b]]);
+    let top: Vec<Perm> = h
+        .iter()
+        .map(|y| {
+            coordinate_action(&radices, |t| {
+                let old = t.to_vec();
+                for b in 0..k {
+                    t[y[b]] = old[b];
+                }
+            })
+        })
+        .collect();
+    wreath(n, g, k, h, degree, block_action, top)
+}
+
+fn wreath(
+    n: usize,
+    g: &[Perm],
+    k: usize,
+    h: &[Perm],
+    degree: usize,
+    block_action: impl Fn(usize, &Perm) -> Perm,
+    top: Vec<Perm>,
+) -> Product {
+    // The base group is generated by copies of G in one block of each H-orbit
+    // together with the top group.
+    let mut representative = vec![true; k];
+    for b in 0..k {
+        if representative[b] {
+            for c in orbit(k, h, b).into_iter().skip(1) {
+                representative[c] = false;
+            }
+        }
+    }
+    let mut generators: Vec<Perm> = (0..k)
+        .filter(|&b| representative[b])
+        .flat_map(|b| g.iter().map(|x| block_action(b, x)).collect::<Vec<_>>())
+        .collect();
+    let base_len = generators.len();
+    generators.extend(top.iter().cloned());
+    let embeddings = (0..k)
+        .map(|b| {
+            let images: Vec<Perm> = g.iter().map(|x| block_action(b, x)).collect();
+            Homomorphism::new(n, degree, g, &images).unwrap()
+        })
+        .chain(std::iter::once(
+            Homomorphism::new(k, degree, h, &top).unwrap(),
+        ))
+        .collect();
+    let projected: Vec<Perm> = std::iter::repeat_n(Perm::e(k), base_len)
+        .chain(h.iter().cloned())
+        .collect();
+    let projections = vec![Homomorphism::new(degree, k, &generators, &projected).unwrap()];
+    Product {
+        degree,
+        generators,
+        embeddings,
+        projections,
+    }
+}
+
+/// Returns the semidirect product N ⋊ H, where N = \<normal\> is of degree n and
+/// H acts on N through action: H -> S_n, whose image must normalize N.
+///
+/// The product acts on n + m points, where m is the degree of H:
+/// x in N acts as (x, e), and y in H acts as (action(y), y).
+/// The embeddings are those of N and H, and the only projection is onto H.
+/// Returns Err if action does not map into S_n or its image does not normalize N.
+pub fn semidirect_product(
+    n: usize,
+    normal: &[Perm],
+    action: &Homomorphism,
+) -> Result<Product, String> {
+    if action.image_degree() != n {
+        return Err(format!(
+            "the action is of degree {}, not {}",
+            action.image_degree(),
+            n
+        ));
+    }
+    let mut rnd = rand::thread_rng();
+    let (normal_transversals, _) = incrementally_build_bsgs(n, &[], normal, &mut rnd);
+    for y in action.image() {
+        for x in normal {
+            let conj = y.inv().compose(x).compose(y);
+            if strip(&conj, &normal_transversals).1 != Perm::e(n) {
+                return Err(format!("{:?} does not normalize the group", y));
+            }
+        }
+    }
+    let m = action.domain_degree();
+    let h = action.domain();
+    let degree = n + m;
+    let normal_images: Vec<Perm> = normal.iter().map(|x| x.concat(&Perm::e(m))).collect();
+    let h_images: Vec<Perm> = action
+        .image()
+        .iter()
+        .zip(h)
+        .map(|(a, y)| a.concat(y))
+        .collect();
+    let generators = [normal_images.clone(), h_images.clone()].concat();
+    let embeddings = vec![
+        Homomorphism::new(n, degree, normal, &normal_images).unwrap(),
+        Homomorphism::new(m, degree, h, &h_images).unwrap(),
+    ];
+    let projected: Vec<Perm> = std::iter::repeat_n(Perm::e(m), normal.len())
+        .chain(h.iter().cloned())
+        .collect();
+    let projections = vec![Homomorphism::new(degree, m, &generators, &projected).unwrap()];
+    Ok(Product {
+        degree,
+        generators,
+        embeddings,
+        projections,
+    })
+}
+
+/// Returns the permutation of the mixed-radix tuples induced by f, which modifies a tuple.
+fn coordinate_action(radices: &[usize], f: impl Fn(&mut [usize])) -> Perm {
+    let degree: usize = radices.iter().product();
+    let mut tuple = vec![0; radices.len()];
+    let mut image = vec![0; degree];
+    for (p, image) in image.iter_mut().enumerate() {
+        let mut rest = p;
+        for (t, &r) in tuple.iter_mut().zip(radices).rev() {
+            *t = rest % r;
+            rest /= r;
+        }
+        f(&mut tuple);
+        *image = tuple
+            .iter()
+            .zip(radices)
+            .fold(0, |acc, (&t, &r)| acc * r + t);
+    }
+    Perm::new(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groups::symmetric;
+    use crate::schreier::order;
+
+    #[test]
+    fn direct_product_test() {
+        let c4 = vec![Perm::new(vec![1, 2, 3, 0])];
+        let factors = vec![(3, symmetric::generators(3).1), (4, c4)];
+        let p = direct_product(&factors);
+        assert_eq!(p.degree, 7);
+        assert_eq!(order(p.degree, &p.generators), 24.into());
+        // The kernel of the projection onto S_3 is C_4.
+        assert_eq!(order(p.degree, &p.projections[0].kernel()), 4.into());
+        let q = direct_product_product_action(&factors);
+        assert_eq!(q.degree, 12);
+        assert_eq!(order(q.degree, &q.generators), 24.into());
+        assert_eq!(orbit(q.degree, &q.generators, 0).len(), 12);
+        let x = Perm::new(vec![1, 0, 2]);
+        let y = q.embeddings[0].apply(&x).unwrap();
+        assert_eq!(q.projections[0].apply(&y), Some(x));
+        assert_eq!(q.projections[1].apply(&y), Some(Perm::e(4)));
+    }
+    #[test]
+    fn wreath_product_test() {
+        // S_2 wr S_3, the hyperoctahedral group of order 48
+        let p = wreath_product(
+            2,
+            &symmetric::generators(2).1,
+            3,
+            &symmetric::generators(3).1,
+        );
+        assert_eq!(p.degree, 6);
+        assert_eq!(order(p.degree, &p.generators), 48.into());
+        assert_eq!(order(p.degree, &p.projections[0].kernel()), 8.into());
+        assert_eq!(p.embeddings.len(), 4);
+        // S_3 wr S_2 in product action on 9 points, of order 72
+        let q = wreath_product_product_action(
+            3,
+            &symmetric::generators(3).1,
+            2,
+            &symmetric::generators(2).1,
+        );
+        assert_eq!(q.degree, 9);
+        assert_eq!(order(q.degree, &q.generators), 72.into());
+        // The top group swaps the coordinates: (1, 2) = 5 <-> (2, 1) = 7.
+        let swap = q.embeddings[2].apply(&Perm::new(vec![1, 0])).unwrap();
+        assert_eq!(swap[5], 7);
+    }
+    #[test]
+    fn semidirect_product_test() {
+        // C_7 ⋊ C_3, where the generator of C_3 acts as x |-> 2x
+        let c7 = vec![Perm::new(vec![1, 2, 3, 4, 5, 6, 0])];
+        let c3 = vec![Perm::new(vec![1, 2, 0])];
+        let double = Perm::new((0..7).map(|x| 2 * x % 7).collect());
+        let action = Homomorphism::new(3, 7, &c3, &[double]).unwrap();
+        let p = semidirect_product(7, &c7, &action).unwrap();
+        assert_eq!(p.degree, 10);
+        assert_eq!(order(p.degree, &p.generators), 21.into());
+        assert_eq!(order(p.degree, &p.projections[0].kernel()), 7.into());
+    }
+    #[test]
+    fn semidirect_product_test_reject() {
+        let c7 = vec![Perm::new(vec![1, 2, 3, 4, 5, 6, 0])];
+        let c2 = vec![Perm::new(vec![1, 0])];
+        // (0 1) does not normalize C_7.
+        let swap = Perm::new(vec![1, 0, 2, 3, 4, 5, 6]);
+        let action = Homomorphism::new(2, 7, &c2, &[swap]).unwrap();
+        assert!(semidirect_product(7, &c7, &action).is_err());
+        // The action must be on the 7 points of C_7.
+        let action = Homomorphism::new(2, 2, &c2, &c2).unwrap();
+        assert!(semidirect_product(7, &c7, &action).is_err());
+    }
+}
@@ -1,20 +1,26 @@
-use crate::perm::Perm;
-use crate::transversal::{get_transversal, orbit_transversal_stabilizer, Transversal};
-use rand::Rng;
+use crate::giant::{giant_order, ProductReplacement};
+use crate::perm::{Perm, Point};
+use crate::transversal::{orbit, transversal_stabilizer, Transversal};
+use num_bigint::BigInt;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 // Reference: https://blogs.cs.st-andrews.ac.uk/codima/files/2015/11/CoDiMa2015_Holt.pdf
 
 /// The returned vaule ([u0, ...], h) must satisfy g = u0 * ... * h.
-pub fn strip(g: &Perm, beta_transversals: &[(usize, Transversal)]) -> (Vec<Perm>, Perm) {
+pub fn strip<P: Point>(
+    g: &Perm<P>,
+    beta_transversals: &[(usize, Transversal<P>)],
+) -> (Vec<Perm<P>>, Perm<P>) {
     let mut h = g.clone();
     let mut us = vec![];
     for &(beta, ref transversal) in beta_transversals {
-        let moved_to = h[beta];
+        let moved_to = h.image(beta);
         match transversal[moved_to] {
             // If repr is dummy, that is, moved_to is not in the orbit beta^H
             None => break,
             Some(ref repr) => {
-                h = h.compose(&repr.inv());
+                h *= transversal.inverse(moved_to).unwrap();
                 us.push(repr.clone());
             }
         }
@@ -22,97 +28,299 @@ pub fn strip(g: &Perm, beta_transversals: &[(usize, Transversal)]) -> (Vec<Perm>
     (us, h)
 }
 
-pub fn schreier_sims(
-    n: usize,
-    beta_transversals: &[(usize, Transversal)],
-    s: &[Perm],
-) -> Result<(), (Vec<Perm>, Perm)> {
-    if beta_transversals.is_empty() {
-        if s.is_empty() {
-            return Ok(());
+/// The same as strip, but returns only the residue h, without collecting the representatives.
+pub fn sift<P: Point>(g: &Perm<P>, beta_transversals: &[(usize, Transversal<P>)]) -> Perm<P> {
+    let mut h = g.clone();
+    for &(beta, ref transversal) in beta_transversals {
+        match transversal.inverse(h.image(beta)) {
+            None => break,
+            Some(repr_inv) => h *= repr_inv,
         }
-        return Err((vec![], s[0].clone()));
     }
-    let mut intersection = Vec::new();
-    // The first fixed point
-    let beta0 = beta_transversals[0].0;
-    for s in s {
-        if s[beta0] == beta0 {
-            intersection.push(s.clone());
-        }
+    h
+}
+
+/// Checks if s is a strong generating set with the base of beta_transversals.
+/// Otherwise returns the strip of an element that does not sift to the identity.
+///
+/// The levels are checked from the last one up, so that a failure is reported at the
+/// deepest level where it occurs.
+pub fn schreier_sims<P: Point>(
+    n: usize,
+    beta_transversals: &[(usize, Transversal<P>)],
+    s: &[Perm<P>],
+) -> Result<(), (Vec<Perm<P>>, Perm<P>)> {
+    let base: Vec<usize> = beta_transversals.iter().map(|&(beta, _)| beta).collect();
+    // Identities in s do not need any base points.
+    if let Some(g) = s
+        .iter()
+        .find(|g| !g.is_identity() && base.iter().all(|&b| g.image(b) == b))
+    {
+        return Err((vec![], g.clone()));
     }
-    schreier_sims(n, &beta_transversals[1..], &intersection)?;
-    let (_, y) = orbit_transversal_stabilizer(n, s, beta0);
-    for y in y {
-        let (us, rest) = strip(&y, &beta_transversals[1..]);
-        if rest != Perm::e(n) {
-            return Err((us, rest));
+    for level in (0..base.len()).rev() {
+        let (_, y) = build_level(n, s, &base, level);
+        if let Some(i) = first_residue(&y, &beta_transversals[level + 1..]) {
+            return Err(strip(&y[i], &beta_transversals[level + 1..]));
         }
     }
     Ok(())
 }
 
-/// Returns B and S built.
-pub fn incrementally_build_bsgs(
+/// Returns the transversal of the orbit of base\[level\] under the elements of s fixing
+/// base\[..level\], and the Schreier generators of its stabilizer.
+fn build_level<P: Point>(
+    n: usize,
+    s: &[Perm<P>],
+    base: &[usize],
+    level: usize,
+) -> (Transversal<P>, Vec<Perm<P>>) {
+    let gen: Vec<Perm<P>> = s
+        .iter()
+        .filter(|g| base[..level].iter().all(|&b| g.image(b) == b))
+        .cloned()
+        .collect();
+    transversal_stabilizer(n, &gen, base[level])
+}
+
+/// Returns the index of the first element of elements whose residue by sift is not
+/// the identity, or None if all of them are in the group of beta_transversals.
+///
+/// With the parallel feature, the elements are sifted on all threads of the rayon pool.
+/// The result is the same as without it, so that it only depends on the order of elements.
+pub fn first_residue<P: Point>(
+    elements: &[Perm<P>],
+    beta_transversals: &[(usize, Transversal<P>)],
+) -> Option<usize> {
+    let is_nontrivial = |g: &Perm<P>| !sift(g, beta_transversals).is_identity();
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        elements.par_iter().position_first(is_nontrivial)
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        elements.iter().position(is_nontrivial)
+    }
+}
+
+/// The base points with the transversals of the stabilizer chain
+pub type StabilizerChain<P = usize> = Vec<(usize, Transversal<P>)>;
+
+/// The number of random elements in a row that have to sift to the identity before
+/// the randomized construction stops, if the order is not known
+const RANDOM_SIFTS: usize = 40;
+/// The number of random elements sifted at a time by the randomized construction
+const RANDOM_BATCH: usize = 8;
+/// The number of random elements whose orders are inspected by exponent
+const EXPONENT_SAMPLES: usize = 1000;
+/// The largest order of a Sylow subgroup whose elements are enumerated by exponent
+const EXPONENT_ELEMENTS: usize = 1 << 16;
+
+/// Options of build_bsgs
+#[derive(Clone, Debug, Default)]
+pub struct BsgsOptions {
+    /// |G|, if known. The construction stops as soon as the product of the orbit lengths
+    /// reaches it. A value less than |G| gives a wrong result, while a larger one only
+    /// prevents stopping early.
+    pub known_order: Option<BigInt>,
+    /// The first base points, e.g. a known base of G. More points are added as needed.
+    pub known_base: Vec<usize>,
+    /// Whether to sift random elements of G by product replacement instead of all Schreier
+    /// generators. Without known_order, the result is a BSGS with high probability only.
+    /// With it, the deterministic construction finishes the job if random elements stop
+    /// extending the BSGS before the order is reached.
+    pub randomized: bool,
+}
+
+/// Returns B and S built, starting with the base points initial_beta.
+pub fn incrementally_build_bsgs<P: Point>(
     n: usize,
     initial_beta: &[usize],
-    initial_s: &[Perm],
+    initial_s: &[Perm<P>],
+    rnd: impl Rng,
+) -> (StabilizerChain<P>, Vec<Perm<P>>) {
+    let options = BsgsOptions {
+        known_base: initial_beta.to_vec(),
+        ..Default::default()
+    };
+    build_bsgs(n, initial_s, &options, rnd)
+}
+
+/// Returns a base and strong generating set of G = \<gen\>, with S containing gen.
+///
+/// The deterministic construction is Sims' algorithm, which works at the lowest level
+/// that is not verified yet. The levels below it form a BSGS of the stabilizer of the base
+/// points above them, and are never checked again. If a Schreier generator of the level
+/// does not sift to the identity, its residue is added to S and the work moves down to
+/// the level where sifting stopped, or to a new base point moved by the residue.
+pub fn build_bsgs<P: Point>(
+    n: usize,
+    gen: &[Perm<P>],
+    options: &BsgsOptions,
     mut rnd: impl Rng,
-) -> (Vec<(usize, Transversal)>, Vec<Perm>) {
-    let mut beta_transversals = vec![];
-    let mut s = initial_s.to_vec();
-    let mut used = vec![false; n];
-    let dummy_transversal = vec![]; // dummy transversals
-    for &beta in initial_beta {
-        beta_transversals.push((beta, dummy_transversal.clone()));
-    }
-    loop {
-        // preliminary result
-        // TODO reuse transversals
-        {
-            let mut cur_s = s.clone();
-            for &mut (beta, ref mut transversal_ref) in &mut beta_transversals {
-                let (orbit_transversal, _) = orbit_transversal_stabilizer(n, &cur_s, beta);
-                let transversal = get_transversal(n, orbit_transversal);
-                *transversal_ref = transversal;
-                cur_s.retain(|perm| perm[beta] == beta);
-                used[beta] = true;
+) -> (StabilizerChain<P>, Vec<Perm<P>>) {
+    let mut beta_transversals: StabilizerChain<P> = options
+        .known_base
+        .iter()
+        .map(|&beta| (beta, Transversal::default()))
+        .collect();
+    let mut s = gen.to_vec();
+    for g in gen {
+        extend_base(g, &mut beta_transversals, &mut rnd);
+    }
+    let mut base: Vec<usize> = beta_transversals.iter().map(|&(beta, _)| beta).collect();
+    // The product of the orbit lengths is at most |G|, with equality exactly when
+    // the transversals and S form a BSGS.
+    let is_complete = |beta_transversals: &[(usize, Transversal<P>)]| {
+        options
+            .known_order
+            .as_ref()
+            .is_some_and(|order| bsgs_order(beta_transversals) == *order)
+    };
+    if options.known_order.is_some() || options.randomized {
+        for (level, (_, transversal)) in beta_transversals.iter_mut().enumerate() {
+            *transversal = build_level(n, &s, &base, level).0;
+        }
+    }
+    if options.randomized {
+        let child = StdRng::from_rng(&mut rnd).unwrap();
+        let mut random = ProductReplacement::with_rng(n, gen, child);
+        // The number of random elements in a row that sifted to the identity
+        let mut trivial = 0;
+        while !is_complete(&beta_transversals) && trivial < RANDOM_SIFTS {
+            let batch: Vec<Perm<P>> = random.by_ref().take(RANDOM_BATCH).collect();
+            let Some(i) = first_residue(&batch, &beta_transversals) else {
+                trivial += batch.len();
+                continue;
+            };
+            trivial = 0;
+            let (us, h) = strip(&batch[i], &beta_transversals);
+            // h fixes the base points above the level where sifting stopped.
+            let stopped = us.len();
+            extend_base(&h, &mut beta_transversals, &mut rnd);
+            base = beta_transversals.iter().map(|&(beta, _)| beta).collect();
+            s.push(h);
+            for (level, (_, transversal)) in beta_transversals[..=stopped].iter_mut().enumerate() {
+                *transversal = build_level(n, &s, &base, level).0;
             }
         }
+        // Without the order, the result is probably correct. With it, the result is either
+        // correct or finished by the deterministic construction below.
+        if options.known_order.is_none() || is_complete(&beta_transversals) {
+            return (beta_transversals, s);
+        }
+    }
+    // The levels from unverified on are verified.
+    let mut unverified = beta_transversals.len();
+    while unverified > 0 && !is_complete(&beta_transversals) {
+        let level = unverified - 1;
+        let (transversal, y) = build_level(n, &s, &base, level);
+        beta_transversals[level].1 = transversal;
+        let Some(i) = first_residue(&y, &beta_transversals[level + 1..]) else {
+            unverified = level;
+            continue;
+        };
+        let (us, h) = strip(&y[i], &beta_transversals[level + 1..]);
+        // h fixes the base points above the level where sifting stopped.
+        let stopped = level + 1 + us.len();
+        extend_base(&h, &mut beta_transversals, &mut rnd);
+        base = beta_transversals.iter().map(|&(beta, _)| beta).collect();
+        s.push(h);
+        unverified = stopped + 1;
+    }
+    (beta_transversals, s)
+}
 
-        // Incrementally computes Y and check if it's okay.
-        match schreier_sims(n, &beta_transversals, &s) {
-            Ok(()) => break,
-            Err((_, h)) => {
-                s.push(h.clone());
-                // Are there any points that are not stabilized by h
-                // and in beta?
-                let mut moved = vec![];
-                for i in 0..n {
-                    if h[i] != i {
-                        if used[i] {
-                            moved.clear();
-                            break;
-                        }
-                        moved.push(i);
-                    }
-                }
-                if !moved.is_empty() {
-                    // All points that are not stabilized by h are not in beta.
-                    // randomly pick one of them
-                    let point = moved[rnd.gen_range(0..moved.len())];
-                    beta_transversals.push((point, dummy_transversal.clone()));
-                }
+/// If g is not the identity but fixes all base points, appends a random point moved by g
+/// to the base.
+fn extend_base<P: Point>(
+    g: &Perm<P>,
+    beta_transversals: &mut StabilizerChain<P>,
+    mut rnd: impl Rng,
+) {
+    if beta_transversals
+        .iter()
+        .any(|&(beta, _)| g.image(beta) != beta)
+    {
+        return;
+    }
+    let moved: Vec<usize> = (0..g.size()).filter(|&i| g.image(i) != i).collect();
+    if !moved.is_empty() {
+        let point = moved[rnd.gen_range(0..moved.len())];
+        beta_transversals.push((point, Transversal::default()));
+    }
+}
+
+/// Returns a subset of s that is still a strong generating set with the base of
+/// beta_transversals, where (beta_transversals, s) is a BSGS.
+///
+/// From the last level up, a generator whose first moved base point is the one of the level
+/// is dropped if the other generators fixing the base points above still move it around
+/// its whole fundamental orbit. Generators added later are tried first.
+pub fn reduce_strong_generators<P: Point>(
+    n: usize,
+    beta_transversals: &[(usize, Transversal<P>)],
+    s: &[Perm<P>],
+) -> Vec<Perm<P>> {
+    let base: Vec<usize> = beta_transversals.iter().map(|&(beta, _)| beta).collect();
+    // The index of the first base point moved by each generator, or None for the identity
+    let levels: Vec<Option<usize>> = s
+        .iter()
+        .map(|g| base.iter().position(|&b| g.image(b) != b))
+        .collect();
+    let mut kept: Vec<bool> = (0..s.len())
+        .map(|i| levels[i].is_some() && !s[..i].contains(&s[i]))
+        .collect();
+    for (level, &(beta, ref transversal)) in beta_transversals.iter().enumerate().rev() {
+        let orbit_len = transversal.iter().filter(|x| x.is_some()).count();
+        for i in (0..s.len()).rev() {
+            if !kept[i] || levels[i] != Some(level) {
+                continue;
+            }
+            kept[i] = false;
+            let gen: Vec<Perm<P>> = (0..s.len())
+                .filter(|&j| kept[j] && levels[j] >= Some(level))
+                .map(|j| s[j].clone())
+                .collect();
+            if orbit(n, &gen, beta).len() != orbit_len {
+                kept[i] = true;
             }
         }
     }
-    (beta_transversals, s)
+    (0..s.len())
+        .filter(|&i| kept[i])
+        .map(|i| s[i].clone())
+        .collect()
 }
 
-pub fn order(n: usize, gen: &[Perm]) -> num_bigint::BigInt {
-    let mut order = 1.into();
+/// Removes the base points whose fundamental orbits are trivial. The result is a BSGS with
+/// the same strong generators, since the stabilizer chain has the same groups.
+pub fn remove_redundant_base_points<P: Point>(
+    beta_transversals: StabilizerChain<P>,
+) -> StabilizerChain<P> {
+    beta_transversals
+        .into_iter()
+        .filter(|(_, transversal)| transversal.iter().flatten().count() > 1)
+        .collect()
+}
+
+pub fn order<P: Point>(n: usize, gen: &[Perm<P>]) -> num_bigint::BigInt {
+    // S_n and A_n are recognized without building a BSGS.
+    if n >= 8 {
+        let gen: Vec<Perm> = gen.iter().map(Perm::cast).collect();
+        if let Some(order) = giant_order(n, &gen) {
+            return order;
+        }
+    }
     let mut rnd = rand::thread_rng();
     let (beta_transversals, _) = incrementally_build_bsgs(n, &[], gen, &mut rnd);
+    bsgs_order(&beta_transversals)
+}
+
+/// Returns \Prod |U_i|, which is the order of the group if beta_transversals is a BSGS.
+pub fn bsgs_order<P: Point>(beta_transversals: &[(usize, Transversal<P>)]) -> num_bigint::BigInt {
+    let mut order = 1.into();
     for (_, transversal) in beta_transversals {
         let mut u = 0;
         for transversal in transversal {
@@ -125,10 +333,140 @@ pub fn order(n: usize, gen: &[Perm]) -> num_bigint::BigInt {
     order
 }
 
+/// Returns the prime factorization of |G| as (prime, exponent) in ascending order of primes.
+pub fn order_factorized<P: Point>(n: usize, gen: &[Perm<P>]) -> Vec<(usize, usize)> {
+    let mut rnd = rand::thread_rng();
+    let (beta_transversals, _) = incrementally_build_bsgs(n, &[], gen, &mut rnd);
+    bsgs_order_factorized(&beta_transversals)
+}
+
+/// Returns the prime factorization of \Prod |U_i|.
+/// Only the orbit lengths |U_i|, which are at most n, are factorized.
+pub fn bsgs_order_factorized<P: Point>(
+    beta_transversals: &[(usize, Transversal<P>)],
+) -> Vec<(usize, usize)> {
+    let mut exponents = std::collections::BTreeMap::new();
+    for (_, transversal) in beta_transversals {
+        let mut u = transversal.iter().filter(|x| x.is_some()).count();
+        let mut p = 2;
+        while p * p <= u {
+            while u.is_multiple_of(p) {
+                *exponents.entry(p).or_insert(0) += 1;
+                u /= p;
+            }
+            p += 1;
+        }
+        if u > 1 {
+            *exponents.entry(u).or_insert(0) += 1;
+        }
+    }
+    exponents.into_iter().collect()
+}
+
+/// Returns the exponent of G = \<gen\>, the least common multiple of the orders of its elements.
+///
+/// The p-part of the exponent divides |G| and is at most the longest orbit of G, since each
+/// cycle of an element lies in an orbit. Orders of random elements are collected until
+/// every p-part reaches this bound. For the remaining primes, random elements of a Sylow
+/// p-subgroup P, whose orders are their longest cycles, are inspected, and then all its
+/// elements until one reaches the bound given by the orbits of P.
+/// Returns None if such P has more than EXPONENT_ELEMENTS elements.
+pub fn exponent(n: usize, gen: &[Perm]) -> Option<BigInt> {
+    let mut rnd = rand::thread_rng();
+    let (beta_transversals, _) = incrementally_build_bsgs(n, &[], gen, &mut rnd);
+    let longest_orbit = |gen: &[Perm]| (0..n).map(|v| orbit(n, gen, v).len()).max();
+    let bound = longest_orbit(gen).unwrap_or(1);
+    // (p, the largest p-part of the orders found, its upper bound)
+    let mut parts: Vec<(usize, usize, usize)> = bsgs_order_factorized(&beta_transversals)
+        .into_iter()
+        .map(|(p, e)| (p, 1, largest_power(p, e, bound)))
+        .collect();
+    for _ in 0..EXPONENT_SAMPLES {
+        if parts.iter().all(|&(_, found, bound)| found == bound) {
+            break;
+        }
+        let lengths = random_element(n, &beta_transversals, &mut rnd).cycle_type();
+        for (p, found, _) in &mut parts {
+            for &len in &lengths {
+                *found = (*found).max(p_part(len, *p));
+            }
+        }
+    }
+    for (p, found, bound) in &mut parts {
+        if *found == *bound {
+            continue;
+        }
+        let sylow = crate::sylow::sylow_subgroup(n, gen, *p);
+        let bound = (*bound).min(longest_orbit(&sylow).unwrap_or(1));
+        let (sylow_transversals, _) = incrementally_build_bsgs(n, &[], &sylow, &mut rnd);
+        for _ in 0..EXPONENT_SAMPLES {
+            if *found >= bound {
+                break;
+            }
+            let x = random_element(n, &sylow_transversals, &mut rnd);
+            *found = (*found).max(*x.cycle_type().last().unwrap_or(&1));
+        }
+        if *found >= bound {
+            continue;
+        }
+        if bsgs_order(&sylow_transversals) > EXPONENT_ELEMENTS.into() {
+            return None;
+        }
+        for x in crate::elements::elements(n, &sylow_transversals) {
+            if *found >= bound {
+                break;
+            }
+            *found = (*found).max(*x.cycle_type().last().unwrap_or(&1));
+        }
+    }
+    let exponent = parts
+        .iter()
+        .map(|&(_, found, _)| BigInt::from(found))
+        .product();
+    Some(exponent)
+}
+
+/// Returns the largest p^k with k <= e and p^k <= limit.
+fn largest_power(p: usize, e: usize, limit: usize) -> usize {
+    let mut power = 1;
+    for _ in 0..e {
+        if power * p > limit {
+            break;
+        }
+        power *= p;
+    }
+    power
+}
+
+/// Returns the largest power of p dividing x.
+fn p_part(mut x: usize, p: usize) -> usize {
+    let mut part = 1;
+    while x.is_multiple_of(p) {
+        x /= p;
+        part *= p;
+    }
+    part
+}
+
+/// Returns a uniformly random element of the group whose BSGS is beta_transversals.
+pub fn random_element<P: Point>(
+    n: usize,
+    beta_transversals: &[(usize, Transversal<P>)],
+    mut rnd: impl Rng,
+) -> Perm<P> {
+    let mut g = Perm::identity(n);
+    for (_, transversal) in beta_transversals {
+        let reprs: Vec<&Perm<P>> = transversal.iter().flatten().collect();
+        g = reprs[rnd.gen_range(0..reprs.len())].compose(&g);
+    }
+    g
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::groups::{mathieu12, rubik};
+    use crate::transversal::{get_transversal, orbit_transversal_stabilizer};
 
     #[test]
     fn schreier_sims_test() {
@@ -215,6 +553,12 @@ mod tests {
         assert_eq!(order(n, &gen), factorial);
     }
     #[test]
+    fn order_test_trivial() {
+        // G = <e>, |G| = 1
+        let n = 3;
+        assert_eq!(order(n, &[Perm::e(n)]), 1.into());
+    }
+    #[test]
     fn order_test_3() {
         use std::str::FromStr;
         // Rubik's Cube group
@@ -225,9 +569,194 @@ mod tests {
         );
     }
     #[test]
+    fn order_factorized_test() {
+        let (n, gen) = rubik::generators();
+        assert_eq!(
+            order_factorized(n, &gen),
+            vec![(2, 27), (3, 14), (5, 3), (7, 2), (11, 1)]
+        );
+        let (n, gen) = mathieu12::generators();
+        assert_eq!(
+            order_factorized(n, &gen),
+            vec![(2, 6), (3, 3), (5, 1), (11, 1)]
+        );
+        assert_eq!(order_factorized::<usize>(3, &[]), vec![]);
+    }
+    #[test]
     fn order_test_4() {
         // The Mathieu group M12
         let (n, gen) = mathieu12::generators();
         assert_eq!(order(n, &gen), 95040.into());
     }
+    #[test]
+    fn order_test_point_types() {
+        for (n, gen) in [rubik::generators(), mathieu12::generators()] {
+            let expected = order(n, &gen);
+            let gen8: Vec<Perm<u8>> = gen.iter().map(Perm::cast).collect();
+            assert_eq!(order(n, &gen8), expected);
+            let gen16: Vec<Perm<u16>> = gen.iter().map(Perm::cast).collect();
+            assert_eq!(order_factorized(n, &gen16), order_factorized(n, &gen));
+            let gen32: Vec<Perm<u32>> = gen.iter().map(Perm::cast).collect();
+            let mut rnd = rand::thread_rng();
+            let (beta_transversals, _) = incrementally_build_bsgs(n, &[], &gen32, &mut rnd);
+            assert_eq!(bsgs_order(&beta_transversals), expected);
+            let g = random_element(n, &beta_transversals, &mut rnd);
+            assert_eq!(strip(&g, &beta_transversals).1, Perm::identity(n));
+            assert!(sift(&g.compose(&gen32[0]), &beta_transversals).is_identity());
+        }
+    }
+    #[test]
+    fn first_residue_test() {
+        let (n, gen) = mathieu12::generators();
+        let mut rnd = rand::thread_rng();
+        let (beta_transversals, _) = incrementally_build_bsgs(n, &[], &gen, &mut rnd);
+        let mut elements: Vec<Perm> = (0..100)
+            .map(|_| random_element(n, &beta_transversals, &mut rnd))
+            .collect();
+        assert_eq!(first_residue(&elements, &beta_transversals), None);
+        // Odd permutations are not in M12.
+        let mut odd: Vec<usize> = (0..n).collect();
+        odd.swap(0, 1);
+        let odd = Perm::new(odd);
+        elements[70] = odd.clone();
+        elements[40] = odd.compose(&elements[41]);
+        assert_eq!(first_residue(&elements, &beta_transversals), Some(40));
+    }
+    #[test]
+    fn incrementally_build_bsgs_seed_test() {
+        use rand::{rngs::StdRng, SeedableRng};
+        // The same seed gives the same BSGS, with or without the parallel feature.
+        let (n, gen) = rubik::generators();
+        let first = incrementally_build_bsgs(n, &[], &gen, StdRng::seed_from_u64(1));
+        let second = incrementally_build_bsgs(n, &[], &gen, StdRng::seed_from_u64(1));
+        assert_eq!(first, second);
+    }
+    #[test]
+    fn long_base_test() {
+        // G = <(0 1), (2 3), ..., (198 199)>, whose bases have 100 points
+        let n = 200;
+        let gen: Vec<Perm> = (0..n / 2)
+            .map(|i| {
+                let mut p: Vec<usize> = (0..n).collect();
+                p.swap(2 * i, 2 * i + 1);
+                Perm::new(p)
+            })
+            .collect();
+        let mut rnd = rand::thread_rng();
+        let (beta_transversals, s) = incrementally_build_bsgs(n, &[], &gen, &mut rnd);
+        assert_eq!(beta_transversals.len(), 100);
+        assert_eq!(bsgs_order(&beta_transversals), BigInt::from(2).pow(100));
+        assert!(schreier_sims(n, &beta_transversals, &s).is_ok());
+    }
+    #[test]
+    fn build_bsgs_options_test() {
+        // S_10 = <(0 1 ... 9), (0 1)> with the base [0, ..., 8]
+        let n = 10;
+        let gen = vec![
+            Perm::new((0..n).map(|i| (i + 1) % n).collect()),
+            Perm::new([vec![1, 0], (2..n).collect()].concat()),
+        ];
+        let factorial: BigInt = (1..=n).product::<usize>().into();
+        let mut rnd = rand::thread_rng();
+        for randomized in [false, true] {
+            let options = BsgsOptions {
+                known_order: Some(factorial.clone()),
+                known_base: (0..n - 1).collect(),
+                randomized,
+            };
+            let (beta_transversals, s) = build_bsgs(n, &gen, &options, &mut rnd);
+            let base: Vec<usize> = beta_transversals.iter().map(|&(beta, _)| beta).collect();
+            assert_eq!(base, options.known_base);
+            assert_eq!(bsgs_order(&beta_transversals), factorial);
+            assert!(schreier_sims(n, &beta_transversals, &s).is_ok());
+        }
+        // A wrong order larger than |S_10| only prevents stopping early.
+        for randomized in [false, true] {
+            let options = BsgsOptions {
+                known_order: Some(&factorial * 2),
+                known_base: vec![],
+                randomized,
+            };
+            let (beta_transversals, s) = build_bsgs(n, &gen, &options, &mut rnd);
+            assert_eq!(bsgs_order(&beta_transversals), factorial);
+            assert!(schreier_sims(n, &beta_transversals, &s).is_ok());
+        }
+        // Without the order, a randomized BSGS is correct with high probability.
+        let (n, gen) = mathieu12::generators();
+        let options = BsgsOptions {
+            randomized: true,
+            ..Default::default()
+        };
+        let (beta_transversals, _) = build_bsgs(n, &gen, &options, &mut rnd);
+        assert_eq!(bsgs_order(&beta_transversals), 95040.into());
+    }
+    #[test]
+    fn reduce_strong_generators_test() {
+        let (n, gen) = rubik::generators();
+        let mut rnd = rand::thread_rng();
+        let (beta_transversals, s) = incrementally_build_bsgs(n, &[], &gen, &mut rnd);
+        let reduced = reduce_strong_generators(n, &beta_transversals, &s);
+        assert!(reduced.len() <= s.len());
+        assert!(reduced.iter().all(|g| s.contains(g)));
+        assert!(schreier_sims(n, &beta_transversals, &reduced).is_ok());
+        // Duplicates and identities are dropped.
+        let (n, gen) = mathieu12::generators();
+        let (beta_transversals, s) = incrementally_build_bsgs(n, &[], &gen, &mut rnd);
+        let redundant = [&s[..], &s[..], &[Perm::e(n)]].concat();
+        let reduced = reduce_strong_generators(n, &beta_transversals, &redundant);
+        assert!(reduced.len() <= s.len());
+        assert!(schreier_sims(n, &beta_transversals, &reduced).is_ok());
+    }
+    #[test]
+    fn remove_redundant_base_points_test() {
+        // G = <(1 2 3), (1 2)> fixes 0 and 4.
+        let n = 5;
+        let gen = vec![
+            Perm::new(vec![0, 2, 3, 1, 4]),
+            Perm::new(vec![0, 2, 1, 3, 4]),
+        ];
+        let mut rnd = rand::thread_rng();
+        let (beta_transversals, s) = incrementally_build_bsgs(n, &[0, 4, 1], &gen, &mut rnd);
+        let beta_transversals = remove_redundant_base_points(beta_transversals);
+        let base: Vec<usize> = beta_transversals.iter().map(|&(beta, _)| beta).collect();
+        assert_eq!(base[0], 1);
+        assert_eq!(base.len(), 2);
+        assert_eq!(bsgs_order(&beta_transversals), 6.into());
+        assert!(schreier_sims(n, &beta_transversals, &s).is_ok());
+    }
+    #[test]
+    fn exponent_test() {
+        // S_4 has elements of orders 1, 2, 3 and 4.
+        let n = 4;
+        let gen = vec![Perm::new(vec![1, 2, 3, 0]), Perm::new(vec![1, 0, 2, 3])];
+        assert_eq!(exponent(n, &gen), Some(12.into()));
+        // M12 has elements of orders 1, 2, 3, 4, 5, 6, 8, 10 and 11, but none of order 9,
+        // which the orbits allow.
+        let (n, gen) = mathieu12::generators();
+        assert_eq!(exponent(n, &gen), Some(1320.into()));
+        let (n, gen) = rubik::generators();
+        assert_eq!(exponent(n, &gen), Some(55440.into()));
+        assert_eq!(exponent(3, &[]), Some(1.into()));
+    }
+    #[test]
+    fn exponent_test_limit() {
+        // (C_2 x C_2)^k, each factor acting regularly on 4 points, has exponent 2,
+        // while its orbits allow 4.
+        let klein = |k: usize| -> Vec<Perm> {
+            (0..k)
+                .flat_map(|i| {
+                    [vec![1, 0, 3, 2], vec![2, 3, 0, 1]].map(|images| {
+                        let mut g: Vec<usize> = (0..4 * k).collect();
+                        for (x, y) in images.into_iter().enumerate() {
+                            g[4 * i + x] = 4 * i + y;
+                        }
+                        Perm::new(g)
+                    })
+                })
+                .collect()
+        };
+        assert_eq!(exponent(8, &klein(2)), Some(2.into()));
+        // The Sylow 2-subgroup of order 2^18 is too large to enumerate.
+        assert_eq!(exponent(36, &klein(9)), None);
+    }
 }
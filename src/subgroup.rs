@@ -0,0 +1,118 @@
+use crate::backtrack::subgroup_search;
+use crate::perm::Perm;
+use crate::schreier::{incrementally_build_bsgs, strip};
+use crate::transversal::Transversal;
+
+/// Returns a generator set of A ∩ B, where A = \<a\> and B = \<b\>.
+///
+/// The search runs over the BSGS of A. The BSGS of B is built with the same
+/// initial base, so that a sequence of base images of an element of A can be
+/// checked against the stabilizer chain of B level by level.
+pub fn intersection(n: usize, a: &[Perm], b: &[Perm]) -> Vec<Perm> {
+    let mut rnd = rand::thread_rng();
+    let (a_transversals, _) = incrementally_build_bsgs(n, &[], a, &mut rnd);
+    let base: Vec<usize> = a_transversals.iter().map(|&(beta, _)| beta).collect();
+    let (b_transversals, _) = incrementally_build_bsgs(n, &base, b, &mut rnd);
+    subgroup_search(
+        n,
+        &a_transversals,
+        &[],
+        |g| strip(g, &b_transversals).1 == Perm::e(n),
+        |images| is_base_image(n, &b_transversals, images),
+    )
+}
+
+/// Returns g^{-1} H g as a generator set, where H = \<h\>.
+pub fn conjugate(h: &[Perm], g: &Perm) -> Vec<Perm> {
+    let g_inv = g.inv();
+    h.iter().map(|x| g_inv.compose(x).compose(g)).collect()
+}
+
+/// Returns a generator set of the normal closure of H = \<h\> in G = \<gen\>,
+/// the smallest normal subgroup of G containing H.
+pub fn normal_closure(n: usize, gen: &[Perm], h: &[Perm]) -> Vec<Perm> {
+    let mut rnd = rand::thread_rng();
+    let mut closure: Vec<Perm> = vec![];
+    let (mut beta_transversals, mut s) = (vec![], vec![]);
+    // Elements of h and their conjugates are added unless they are already in N = <closure>.
+    // N is normal if x^g is in N for every x in closure and g in gen.
+    let mut que: Vec<Perm> = h.to_vec();
+    que.reverse();
+    while let Some(y) = que.pop() {
+        if strip(&y, &beta_transversals).1 == Perm::e(n) {
+            continue;
+        }
+        let base: Vec<usize> = beta_transversals.iter().map(|&(beta, _)| beta).collect();
+        s.push(y.clone());
+        (beta_transversals, s) = incrementally_build_bsgs(n, &base, &s, &mut rnd);
+        for g in gen {
+            que.push(g.inv().compose(&y).compose(g));
+        }
+        closure.push(y);
+    }
+    closure
+}
+
+/// Returns a generator set of the derived subgroup \[G, G\] of G = \<gen\>.
+pub fn derived_subgroup(n: usize, gen: &[Perm]) -> Vec<Perm> {
+    let mut commutators = vec![];
+    for (i, a) in gen.iter().enumerate() {
+        for b in &gen[..i] {
+            commutators.push(a.inv().compose(&b.inv()).compose(a).compose(b));
+        }
+    }
+    normal_closure(n, gen, &commutators)
+}
+
+/// Checks if there is an element mapping beta_i to gamma_i for every i,
+/// where images\[i\] = (beta_i, gamma_i) and beta_i are the first base points.
+fn is_base_image(
+    n: usize,
+    beta_transversals: &[(usize, Transversal)],
+    images: &[(usize, usize)],
+) -> bool {
+    // g = g' * u, where u is the representative of level i and g' is in the next level.
+    // acc maps gamma_i to the image of beta_i under g'.
+    let mut acc = Perm::e(n);
+    for (&(beta, ref transversal), &(b, c)) in beta_transversals.iter().zip(images) {
+        debug_assert_eq!(beta, b);
+        match transversal.inverse(acc[c]) {
+            None => return false,
+            Some(repr_inv) => acc *= repr_inv,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schreier::order;
+
+    #[test]
+    fn intersection_test() {
+        // A = <(0 1 2 3 4 5)>, B = <(0 3), (1 4), (2 5)>, A ∩ B = <(0 3)(1 4)(2 5)>
+        let n = 6;
+        let a = vec![Perm::new(vec![1, 2, 3, 4, 5, 0])];
+        let b = vec![
+            Perm::new(vec![3, 1, 2, 0, 4, 5]),
+            Perm::new(vec![0, 4, 2, 3, 1, 5]),
+            Perm::new(vec![0, 1, 5, 3, 4, 2]),
+        ];
+        let c = intersection(n, &a, &b);
+        assert_eq!(order(n, &c), 2.into());
+        assert_eq!(order(n, &intersection(n, &b, &a)), 2.into());
+    }
+    #[test]
+    fn normal_closure_test() {
+        // The normal closure of <(0 1)(2 3)> in S_4 is V_4.
+        let n = 4;
+        let s4 = vec![Perm::new(vec![1, 2, 3, 0]), Perm::new(vec![1, 0, 2, 3])];
+        let h = vec![Perm::new(vec![1, 0, 3, 2])];
+        assert_eq!(order(n, &normal_closure(n, &s4, &h)), 4.into());
+        // [S_4, S_4] = A_4, [A_4, A_4] = V_4
+        let a4 = derived_subgroup(n, &s4);
+        assert_eq!(order(n, &a4), 12.into());
+        assert_eq!(order(n, &derived_subgroup(n, &a4)), 4.into());
+    }
+}
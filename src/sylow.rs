@@ -0,0 +1,378 @@
+use crate::blocks::minimal_block;
+use crate::centralizer::centralizer_in_bsgs;
+use crate::giant::giant_order;
+use crate::homomorphism::Homomorphism;
+use crate::integer::lcm;
+use crate::normalizer::normalizer;
+use crate::perm::Perm;
+use crate::schreier::{bsgs_order, first_residue, incrementally_build_bsgs, random_element, strip};
+use crate::subgroup::{conjugate, intersection};
+use crate::transversal::orbit;
+use num_bigint::BigInt;
+
+/// The number of p-elements tried before falling back to the normalizer-based construction
+const CENTRALIZER_TRIES: usize = 10;
+/// The number of random p-elements of N_G(P) sifted through P at a time
+const SIFT_BATCH: usize = 8;
+
+/// Checks if G = \<gen\> is a p-group, that is, |G| is a power of p.
+pub fn is_p_group(n: usize, gen: &[Perm], p: usize) -> bool {
+    let mut rnd = rand::thread_rng();
+    let (beta_transversals, _) = incrementally_build_bsgs(n, &[], gen, &mut rnd);
+    // |G| is the product of the orbit lengths.
+    beta_transversals.iter().all(|(_, transversal)| {
+        let mut len = transversal.iter().filter(|x| x.is_some()).count();
+        while len % p == 0 {
+            len /= p;
+        }
+        len == 1
+    })
+}
+
+/// Returns a generator set of a Sylow p-subgroup of G = \<gen\>. p must be a prime.
+///
+/// If G acts on an orbit or on a block system through φ whose image is not a p-group,
+/// the preimage of a Sylow p-subgroup of the image is a proper subgroup of G
+/// containing a Sylow p-subgroup, and the search recurses into it.
+/// A primitive group recognized as S_n or A_n by giant::is_giant gets an explicit one.
+/// Otherwise, if z is an element of order p in the center of some Sylow p-subgroup,
+/// C_G(z) contains a Sylow p-subgroup of G, so the search recurses into C_G(z)
+/// whenever it is a proper subgroup with the full p-part of |G|. Such elements are looked for
+/// among the p-parts of random elements. If none is found, a p-subgroup P is grown instead
+/// by adding p-elements of N_G(P) \ P until |P| reaches the p-part of |G|,
+/// recursing into N_G(P) as soon as it is a proper subgroup with the full p-part of |G|.
+pub fn sylow_subgroup(n: usize, gen: &[Perm], p: usize) -> Vec<Perm> {
+    let mut rnd = rand::thread_rng();
+    let (beta_transversals, _) = incrementally_build_bsgs(n, &[], gen, &mut rnd);
+    let order = bsgs_order(&beta_transversals);
+    let a = valuation(&order, p);
+    if a == 0 {
+        return vec![];
+    }
+    if BigInt::from(p).pow(a as u32) == order {
+        return gen.to_vec();
+    }
+    for phi in actions(n, gen) {
+        let image_order =
+            bsgs_order(&incrementally_build_bsgs(phi.image_degree(), &[], phi.image(), &mut rnd).0);
+        if BigInt::from(p).pow(valuation(&image_order, p) as u32) < image_order {
+            let image_sylow = sylow_subgroup(phi.image_degree(), phi.image(), p);
+            return sylow_subgroup(n, &phi.subgroup_preimage(&image_sylow).unwrap(), p);
+        }
+    }
+    if let Some(giant_order) = giant_order(n, gen) {
+        let symmetric_order: BigInt = (1..=n).product();
+        return giant_sylow(n, p, giant_order < symmetric_order);
+    }
+    let mut central = vec![];
+    for _ in 0..CENTRALIZER_TRIES {
+        let y = p_part(&random_element(n, &beta_transversals, &mut rnd), p);
+        if y == Perm::e(n) {
+            continue;
+        }
+        // z = y^{p^{k-1}} has order p.
+        let mut z = y;
+        loop {
+            let next = z.pow(p as i64);
+            if next == Perm::e(n) {
+                break;
+            }
+            z = next;
+        }
+        let c = centralizer_in_bsgs(n, &beta_transversals, std::slice::from_ref(&z));
+        let (c_transversals, _) = incrementally_build_bsgs(n, &[], &c, &mut rnd);
+        let c_order = bsgs_order(&c_transversals);
+        if valuation(&c_order, p) == a {
+            if c_order < order {
+                return sylow_subgroup(n, &c, p);
+            }
+            // z is central in G.
+            central.push(z);
+        }
+    }
+    grow_p_subgroup(n, gen, central, p, a)
+}
+
+/// Returns a generator set of O_p(G), the largest normal p-subgroup of G = \<gen\>.
+///
+/// O_p(G) is the intersection of all Sylow p-subgroups,
+/// and is computed as the core of one of them.
+pub fn p_core(n: usize, gen: &[Perm], p: usize) -> Vec<Perm> {
+    let mut rnd = rand::thread_rng();
+    let mut core = sylow_subgroup(n, gen, p);
+    loop {
+        let (core_transversals, _) = incrementally_build_bsgs(n, &[], &core, &mut rnd);
+        let mut changed = false;
+        for g in gen {
+            let conj = conjugate(&core, g);
+            if conj
+                .iter()
+                .any(|x| strip(x, &core_transversals).1 != Perm::e(n))
+            {
+                core = intersection(n, &core, &conj);
+                changed = true;
+                break;
+            }
+        }
+        if !changed {
+            return core;
+        }
+    }
+}
+
+/// Extends a p-subgroup P = <initial> until |P| = p^a.
+fn grow_p_subgroup(n: usize, gen: &[Perm], initial: Vec<Perm>, p: usize, a: usize) -> Vec<Perm> {
+    let mut rnd = rand::thread_rng();
+    let (g_transversals, _) = incrementally_build_bsgs(n, &[], gen, &mut rnd);
+    let mut sub = initial;
+    loop {
+        let (sub_transversals, _) = incrementally_build_bsgs(n, &[], &sub, &mut rnd);
+        if valuation(&bsgs_order(&sub_transversals), p) == a {
+            return sub;
+        }
+        // p divides |N_G(P) : P| unless P is a Sylow p-subgroup.
+        // A p-element y of N_G(P) \ P gives a larger p-subgroup <P, y>.
+        let norm = normalizer(n, gen, &sub);
+        let (norm_transversals, _) = incrementally_build_bsgs(n, &[], &norm, &mut rnd);
+        let norm_order = bsgs_order(&norm_transversals);
+        if valuation(&norm_order, p) == a && norm_order < bsgs_order(&g_transversals) {
+            // N_G(P) is a proper subgroup containing a Sylow p-subgroup.
+            return sylow_subgroup(n, &norm, p);
+        }
+        loop {
+            let batch: Vec<Perm> = (0..SIFT_BATCH)
+                .map(|_| p_part(&random_element(n, &norm_transversals, &mut rnd), p))
+                .collect();
+            if let Some(i) = first_residue(&batch, &sub_transversals) {
+                sub.push(batch[i].clone());
+                break;
+            }
+        }
+    }
+}
+
+/// Returns a generator set of a Sylow p-subgroup of S_n, or of A_n if alternating.
+///
+/// Writing n = d_0 + d_1 p + d_2 p^2 + ... in base p, a Sylow p-subgroup of S_n is
+/// the direct product of d_i Sylow p-subgroups of S_{p^i} on consecutive points.
+/// The one of S_{p^i} on the points 0..p^i is generated by x |-> x + p^{j-1} mod p^j
+/// on the points 0..p^j for j = 1, ..., i.
+fn giant_sylow(n: usize, p: usize, alternating: bool) -> Vec<Perm> {
+    let mut gen = vec![];
+    let mut offset = 0;
+    let mut size = 1;
+    let mut rest = n;
+    while rest > 0 {
+        for _ in 0..rest % p {
+            let mut block = 1;
+            while block < size {
+                let mut images: Vec<usize> = (0..n).collect();
+                for x in 0..block * p {
+                    images[offset + x] = offset + (x + block) % (block * p);
+                }
+                gen.push(Perm::new(images));
+                block *= p;
+            }
+            offset += size;
+        }
+        rest /= p;
+        size *= p;
+    }
+    // For odd p, the p-elements are even.
+    if !alternating || p != 2 {
+        return gen;
+    }
+    // The Schreier generators of the even permutations, with coset representatives e and o
+    let Some(o) = gen.iter().find(|g| is_odd(g)).cloned() else {
+        return gen;
+    };
+    gen.iter()
+        .flat_map(|g| {
+            if !is_odd(g) {
+                [g.clone(), o.compose(g).compose(&o.inv())]
+            } else {
+                [g.compose(&o.inv()), o.compose(g)]
+            }
+        })
+        .filter(|g| *g != Perm::e(n))
+        .collect()
+}
+
+fn is_odd(g: &Perm) -> bool {
+    (g.size() - g.cycle_type().len()) % 2 == 1
+}
+
+/// Returns the actions of G = <gen> on its nontrivial orbits if G is intransitive,
+/// and on its minimal block systems containing 0 otherwise.
+fn actions(n: usize, gen: &[Perm]) -> impl Iterator<Item = Homomorphism> + '_ {
+    let mut seen = vec![false; n];
+    let orbits: Vec<Vec<usize>> = (0..n)
+        .filter_map(|v| {
+            if seen[v] {
+                return None;
+            }
+            let o = orbit(n, gen, v);
+            for &x in &o {
+                seen[x] = true;
+            }
+            Some(o)
+        })
+        .filter(|o| o.len() > 1)
+        .collect();
+    let transitive = orbits.len() == 1 && orbits[0].len() == n;
+    let orbit_actions = orbits
+        .into_iter()
+        .filter(move |_| !transitive)
+        .map(move |o| Homomorphism::action_on_orbit(n, gen, &o));
+    let mut systems = vec![];
+    let block_actions = (1..n).filter(move |_| transitive).filter_map(move |b| {
+        let labels = minimal_block(n, gen, 0, b);
+        if labels.iter().all(|&l| l == 0) || systems.contains(&labels) {
+            return None;
+        }
+        systems.push(labels.clone());
+        Some(Homomorphism::action_on_blocks(n, gen, &labels))
+    });
+    orbit_actions.chain(block_actions)
+}
+
+/// Returns the largest e such that p^e divides x.
+fn valuation(x: &BigInt, p: usize) -> usize {
+    let p = BigInt::from(p);
+    let zero = BigInt::from(0);
+    let mut x = x.clone();
+    let mut e = 0;
+    while &x % &p == zero {
+        x /= &p;
+        e += 1;
+    }
+    e
+}
+
+/// Returns the p-part of x, which is the power of x of p-power order
+/// that generates the Sylow p-subgroup of <x>.
+fn p_part(x: &Perm, p: usize) -> Perm {
+    let n = x.size();
+    let mut cycles = vec![];
+    let mut visited = vec![false; n];
+    for i in 0..n {
+        let mut cycle = vec![];
+        let mut j = i;
+        while !visited[j] {
+            visited[j] = true;
+            cycle.push(j);
+            j = x[j];
+        }
+        if !cycle.is_empty() {
+            cycles.push(cycle);
+        }
+    }
+    // r = lcm of the p'-parts of the cycle lengths
+    let mut r = BigInt::from(1);
+    for cycle in &cycles {
+        let mut len = cycle.len();
+        while len % p == 0 {
+            len /= p;
+        }
+        r = lcm(&r, &len.into());
+    }
+    // x^r shifts each cycle by r.
+    let mut result = vec![0; n];
+    for cycle in &cycles {
+        let len = cycle.len();
+        let shift: usize = (&r % BigInt::from(len)).try_into().unwrap();
+        for i in 0..len {
+            result[cycle[i]] = cycle[(i + shift) % len];
+        }
+    }
+    Perm::new(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groups::{alternating, mathieu12, rubik, symmetric};
+    use crate::schreier::order;
+
+    /// Checks that sylow is a p-subgroup of G = <gen> of order expected.
+    fn check_sylow(n: usize, gen: &[Perm], sylow: &[Perm], p: usize, expected: BigInt) {
+        let mut rnd = rand::thread_rng();
+        let (beta_transversals, _) = incrementally_build_bsgs(n, &[], gen, &mut rnd);
+        for x in sylow {
+            assert_eq!(strip(x, &beta_transversals).1, Perm::e(n));
+        }
+        assert!(is_p_group(n, sylow, p));
+        assert_eq!(order(n, sylow), expected);
+    }
+
+    #[test]
+    fn sylow_subgroup_test_s6() {
+        let n = 6;
+        let gen = symmetric::generators(n).1;
+        for (p, expected) in [(2, 16), (3, 9), (5, 5), (7, 1)] {
+            let sylow = sylow_subgroup(n, &gen, p);
+            assert!(is_p_group(n, &sylow, p));
+            assert_eq!(order(n, &sylow), expected.into());
+        }
+    }
+    #[test]
+    fn sylow_subgroup_test_m12() {
+        // |M12| = 2^6 * 3^3 * 5 * 11
+        let (n, gen) = mathieu12::generators();
+        for (p, expected) in [(2, 64), (3, 27), (5, 5), (11, 11)] {
+            let sylow = sylow_subgroup(n, &gen, p);
+            assert!(is_p_group(n, &sylow, p));
+            assert_eq!(order(n, &sylow), expected.into());
+        }
+        assert!(!is_p_group(n, &gen, 2));
+    }
+    #[test]
+    fn sylow_subgroup_test_rubik() {
+        // |G| = 2^27 * 3^14 * 5^3 * 7^2 * 11
+        let (n, gen) = rubik::generators();
+        for (p, a) in [(2, 27), (3, 14), (5, 3), (7, 2), (11, 1)] {
+            let sylow = sylow_subgroup(n, &gen, p);
+            check_sylow(n, &gen, &sylow, p, BigInt::from(p).pow(a));
+        }
+    }
+    #[test]
+    fn sylow_subgroup_test_giant() {
+        // |S_30|_2 = 2^26, |S_30|_3 = 3^14, |A_9|_2 = 2^6, |A_9|_3 = 3^4
+        for (n, gen, p, a) in [
+            (30, symmetric::generators(30).1, 2, 26),
+            (30, symmetric::generators(30).1, 3, 14),
+            (9, alternating::generators(9).1, 2, 6),
+            (9, alternating::generators(9).1, 3, 4),
+        ] {
+            let sylow = sylow_subgroup(n, &gen, p);
+            check_sylow(n, &gen, &sylow, p, BigInt::from(p).pow(a));
+        }
+    }
+    #[test]
+    fn p_core_test() {
+        // O_2(S_4) = V_4
+        let n = 4;
+        assert_eq!(
+            order(n, &p_core(n, &symmetric::generators(n).1, 2)),
+            4.into()
+        );
+        assert_eq!(
+            order(n, &p_core(n, &symmetric::generators(n).1, 3)),
+            1.into()
+        );
+        // O_2(S_5) = 1
+        let n = 5;
+        assert_eq!(
+            order(n, &p_core(n, &symmetric::generators(n).1, 2)),
+            1.into()
+        );
+    }
+    #[test]
+    fn p_part_test() {
+        // x = (0 1 2 3 4 5)(6 7), x^3 = (0 3)(1 4)(2 5)(6 7)
+        let x = Perm::new(vec![1, 2, 3, 4, 5, 0, 7, 6]);
+        assert_eq!(p_part(&x, 2), x.pow(3));
+        assert_eq!(p_part(&x, 3), x.pow(2));
+        assert_eq!(p_part(&x, 5), Perm::e(8));
+    }
+}
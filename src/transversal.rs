@@ -1,59 +1,168 @@
-use crate::perm::Perm;
-use std::collections::VecDeque;
+use crate::perm::{Perm, Point};
 
 // Reference: https://blogs.cs.st-andrews.ac.uk/codima/files/2015/11/CoDiMa2015_Holt.pdf
 
 // A collection of pairs (x, alpha) s.t. v^alpha = x.
-pub type OrbitTransversal = Vec<(usize, Perm)>;
+pub type OrbitTransversal<P = usize> = Vec<(usize, Perm<P>)>;
 
-// An oracle of type usize -> Option<Perm>.
-pub type Transversal = Vec<Option<Perm>>;
+/// An oracle of type usize -> `Option<Perm>`: transversal\[x\] = Some(u) with v^u = x
+/// if x is in the orbit of v.
+///
+/// The inverses of the representatives are kept as well, so that sifting an element
+/// through a stabilizer chain does not need to invert anything.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transversal<P: Point = usize> {
+    reprs: Vec<Option<Perm<P>>>,
+    inverses: Vec<Option<Perm<P>>>,
+}
+
+impl<P: Point> Transversal<P> {
+    /// Returns u^{-1}, where u = self\[x\].
+    #[inline]
+    pub fn inverse(&self, x: usize) -> Option<&Perm<P>> {
+        self.inverses[x].as_ref()
+    }
+}
+
+/// The empty transversal, a placeholder for a base point whose orbit is not computed yet
+impl<P: Point> Default for Transversal<P> {
+    fn default() -> Self {
+        Transversal {
+            reprs: vec![],
+            inverses: vec![],
+        }
+    }
+}
+
+impl<'a, P: Point> IntoIterator for &'a Transversal<P> {
+    type Item = &'a Option<Perm<P>>;
+    type IntoIter = std::slice::Iter<'a, Option<Perm<P>>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.reprs.iter()
+    }
+}
+
+impl<P: Point> std::ops::Deref for Transversal<P> {
+    type Target = [Option<Perm<P>>];
+    fn deref(&self) -> &Self::Target {
+        &self.reprs
+    }
+}
 
 /// gen: generators, v: stabilized point
 ///
 /// This function returns a generator set of the stabilizer group G_v = Stab_G(v).
-pub fn orbit_transversal_stabilizer(
+pub fn orbit_transversal_stabilizer<P: Point>(
+    n: usize,
+    gen: &[Perm<P>],
+    v: usize,
+) -> (OrbitTransversal<P>, Vec<Perm<P>>) {
+    let (orbit, mut transversal, stabilizer_gen) = search(n, gen, v);
+    let orbit_transversal = orbit
+        .into_iter()
+        .map(|w| (w, transversal.reprs[w].take().unwrap()))
+        .collect();
+    // Returns (Vec of (point, representative), a generator set of G_v)
+    (orbit_transversal, stabilizer_gen)
+}
+
+/// The same as orbit_transversal_stabilizer, but returns the transversal as a Transversal
+/// with the inverses computed during the search.
+pub fn transversal_stabilizer<P: Point>(
     n: usize,
-    gen: &[Perm],
+    gen: &[Perm<P>],
     v: usize,
-) -> (OrbitTransversal, Vec<Perm>) {
+) -> (Transversal<P>, Vec<Perm<P>>) {
+    let (_, transversal, stabilizer_gen) = search(n, gen, v);
+    (transversal, stabilizer_gen)
+}
+
+/// Returns the orbit of v in the order of discovery, its transversal and a generator set of G_v.
+fn search<P: Point>(
+    n: usize,
+    gen: &[Perm<P>],
+    v: usize,
+) -> (Vec<usize>, Transversal<P>, Vec<Perm<P>>) {
     let mut stabilizer_gen = Vec::new();
     // Calculates a variant of the Schreier vector
     // performing breadth-first search.
-    let mut que = VecDeque::new();
-    que.push_back((v, Perm::e(n)));
-    let mut table: Vec<Option<Perm>> = vec![None; n];
-    let mut orbit_transversal = vec![];
-    while let Some((w, p)) = que.pop_front() {
-        if let Some(ref q) = table[w] {
-            // r = qp^{-1} stabilizes v.
-            let r = q.compose(&p.inv());
-            if r != Perm::e(n) {
-                stabilizer_gen.push(r);
-            }
-        } else {
-            for x in gen {
-                // move to w^x (x applied to w).
-                que.push_back((x[w], p.compose(x)));
+    let mut table: Vec<Option<Perm<P>>> = vec![None; n];
+    // The inverses of the entries of table
+    let mut inverses: Vec<Option<Perm<P>>> = vec![None; n];
+    table[v] = Some(Perm::identity(n));
+    inverses[v] = Some(Perm::identity(n));
+    let gen_inv: Vec<Perm<P>> = gen.iter().map(Perm::inv).collect();
+    let mut orbit = vec![v];
+    // Buffers reused for every edge w -> w^x
+    let mut p = Perm::identity(n);
+    let mut r = Perm::identity(n);
+    let mut pos = 0;
+    while pos < orbit.len() {
+        let w = orbit[pos];
+        pos += 1;
+        for (x, x_inv) in gen.iter().zip(&gen_inv) {
+            // move to w^x (x applied to w).
+            let y = x.image(w);
+            match table[y] {
+                None => {
+                    let p = table[w].as_ref().unwrap().compose(x);
+                    inverses[y] = Some(p.inv());
+                    table[y] = Some(p);
+                    orbit.push(y);
+                }
+                Some(ref q) => {
+                    // r = qp^{-1} stabilizes v, where p = table[w]x,
+                    // so that p^{-1} = x^{-1}table[w]^{-1}.
+                    q.compose_into(x_inv, &mut p);
+                    p.compose_into(inverses[w].as_ref().unwrap(), &mut r);
+                    if !r.is_identity() {
+                        stabilizer_gen.push(r.clone());
+                    }
+                }
             }
-            orbit_transversal.push((w, p.clone()));
-            table[w] = Some(p);
         }
     }
     // Remove duplicate generators
     stabilizer_gen.sort();
     stabilizer_gen.dedup();
+    let transversal = Transversal {
+        reprs: table,
+        inverses,
+    };
+    (orbit, transversal, stabilizer_gen)
+}
 
-    // Returns (Vec of (point, representative), a generator set of G_v)
-    (orbit_transversal, stabilizer_gen)
+/// Returns the orbit v^G in the order of discovery by breadth-first search.
+pub fn orbit<P: Point>(n: usize, gen: &[Perm<P>], v: usize) -> Vec<usize> {
+    let mut visited = vec![false; n];
+    let mut orbit = vec![v];
+    visited[v] = true;
+    let mut pos = 0;
+    while pos < orbit.len() {
+        let w = orbit[pos];
+        pos += 1;
+        for x in gen {
+            let y = x.image(w);
+            if !visited[y] {
+                visited[y] = true;
+                orbit.push(y);
+            }
+        }
+    }
+    orbit
 }
 
-pub fn get_transversal(n: usize, orbit_transversal: OrbitTransversal) -> Transversal {
-    let mut transversal = vec![None; n];
+pub fn get_transversal<P: Point>(
+    n: usize,
+    orbit_transversal: OrbitTransversal<P>,
+) -> Transversal<P> {
+    let mut reprs = vec![None; n];
+    let mut inverses = vec![None; n];
     for (point, trans) in orbit_transversal {
-        transversal[point] = Some(trans);
+        inverses[point] = Some(trans.inv());
+        reprs[point] = Some(trans);
     }
-    transversal
+    Transversal { reprs, inverses }
 }
 
 #[cfg(test)]
@@ -73,4 +182,35 @@ mod tests {
         assert_eq!(h3.len(), 2);
         assert_eq!(stab3, Vec::new());
     }
+    #[test]
+    fn transversal_stabilizer_test() {
+        let gen = vec![Perm::new(vec![1, 2, 3, 0]), Perm::new(vec![1, 0, 2, 3])];
+        let (orbit_transversal, stab) = orbit_transversal_stabilizer(4, &gen, 1);
+        let (transversal, stab2) = transversal_stabilizer(4, &gen, 1);
+        assert_eq!(transversal, get_transversal(4, orbit_transversal));
+        assert_eq!(stab, stab2);
+    }
+    #[test]
+    fn orbit_test() {
+        // G = <(0 1 2), (3 4)>
+        let gen = vec![
+            Perm::new(vec![1, 2, 0, 3, 4]),
+            Perm::new(vec![0, 1, 2, 4, 3]),
+        ];
+        assert_eq!(orbit(5, &gen, 1), vec![1, 2, 0]);
+        assert_eq!(orbit(5, &gen, 4), vec![4, 3]);
+    }
+    #[test]
+    fn get_transversal_test() {
+        let gen = vec![Perm::new(vec![1, 2, 3, 0]), Perm::new(vec![1, 0, 2, 3])];
+        let (orbit_transversal, _) = orbit_transversal_stabilizer(4, &gen, 1);
+        let transversal = get_transversal(5, orbit_transversal);
+        assert_eq!(transversal.len(), 5);
+        assert!(transversal[4].is_none() && transversal.inverse(4).is_none());
+        for x in 0..4 {
+            let u = transversal[x].as_ref().unwrap();
+            assert_eq!(u[1], x);
+            assert_eq!(transversal.inverse(x), Some(&u.inv()));
+        }
+    }
 }